@@ -0,0 +1,17 @@
+-- Keep only the most recent review per user per business
+DELETE FROM reviews
+WHERE EXISTS (
+    SELECT 1 FROM reviews AS newer
+    WHERE newer.business_id = reviews.business_id
+      AND newer.user_id = reviews.user_id
+      AND (newer.updated_at > reviews.updated_at
+           OR (newer.updated_at = reviews.updated_at AND newer.rowid > reviews.rowid))
+);
+
+-- Refresh cached ratings after removing duplicates
+UPDATE businesses SET
+    review_count = (SELECT COUNT(*) FROM reviews WHERE reviews.business_id = businesses.id),
+    average_rating = COALESCE((SELECT AVG(rating) FROM reviews WHERE reviews.business_id = businesses.id), 0.0);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_reviews_business_user ON reviews(business_id, user_id);
+CREATE INDEX IF NOT EXISTS idx_reviews_user_created ON reviews(user_id, created_at);
@@ -0,0 +1,11 @@
+-- Create review edit history table
+CREATE TABLE IF NOT EXISTS review_edits (
+    id TEXT PRIMARY KEY,
+    review_id TEXT NOT NULL,
+    previous_rating INTEGER NOT NULL,
+    previous_comment TEXT NOT NULL,
+    edited_at TEXT NOT NULL,
+    FOREIGN KEY (review_id) REFERENCES reviews(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_review_edits_review ON review_edits(review_id);
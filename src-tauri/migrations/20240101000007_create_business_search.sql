@@ -0,0 +1,49 @@
+-- Full-text index over businesses and their review text.
+-- Rows share rowids with `businesses` so triggers can update them directly.
+CREATE VIRTUAL TABLE IF NOT EXISTS business_search USING fts5(
+    name,
+    category,
+    description,
+    reviews,
+    business_id UNINDEXED,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO business_search (rowid, name, category, description, reviews, business_id)
+SELECT b.rowid, b.name, b.category, b.description,
+       COALESCE((SELECT group_concat(r.comment, ' ') FROM reviews r WHERE r.business_id = b.id), ''),
+       b.id
+FROM businesses b;
+
+CREATE TRIGGER IF NOT EXISTS businesses_search_insert AFTER INSERT ON businesses BEGIN
+    INSERT INTO business_search (rowid, name, category, description, reviews, business_id)
+    VALUES (new.rowid, new.name, new.category, new.description, '', new.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS businesses_search_update AFTER UPDATE OF name, category, description ON businesses BEGIN
+    UPDATE business_search
+    SET name = new.name, category = new.category, description = new.description
+    WHERE rowid = new.rowid;
+END;
+
+CREATE TRIGGER IF NOT EXISTS businesses_search_delete AFTER DELETE ON businesses BEGIN
+    DELETE FROM business_search WHERE rowid = old.rowid;
+END;
+
+CREATE TRIGGER IF NOT EXISTS reviews_search_insert AFTER INSERT ON reviews BEGIN
+    UPDATE business_search
+    SET reviews = COALESCE((SELECT group_concat(comment, ' ') FROM reviews WHERE business_id = new.business_id), '')
+    WHERE rowid = (SELECT rowid FROM businesses WHERE id = new.business_id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS reviews_search_update AFTER UPDATE OF comment ON reviews BEGIN
+    UPDATE business_search
+    SET reviews = COALESCE((SELECT group_concat(comment, ' ') FROM reviews WHERE business_id = new.business_id), '')
+    WHERE rowid = (SELECT rowid FROM businesses WHERE id = new.business_id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS reviews_search_delete AFTER DELETE ON reviews BEGIN
+    UPDATE business_search
+    SET reviews = COALESCE((SELECT group_concat(comment, ' ') FROM reviews WHERE business_id = old.business_id), '')
+    WHERE rowid = (SELECT rowid FROM businesses WHERE id = old.business_id);
+END;
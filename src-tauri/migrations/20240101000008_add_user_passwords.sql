@@ -0,0 +1,2 @@
+-- Salted Argon2 password hash (PHC string); NULL for accounts created before sign-up existed
+ALTER TABLE users ADD COLUMN password_hash TEXT;
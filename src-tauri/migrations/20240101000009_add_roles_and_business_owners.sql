@@ -0,0 +1,14 @@
+-- Roles: customers review and favorite, owners manage their businesses, admins moderate everything
+ALTER TABLE users ADD COLUMN role TEXT NOT NULL DEFAULT 'customer' CHECK (role IN ('customer', 'owner', 'admin'));
+
+-- Which users own which businesses
+CREATE TABLE IF NOT EXISTS business_owners (
+    business_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (business_id, user_id),
+    FOREIGN KEY (business_id) REFERENCES businesses(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_business_owners_user ON business_owners(user_id);
@@ -0,0 +1,11 @@
+-- Create owner replies to reviews (at most one per review)
+CREATE TABLE IF NOT EXISTS review_responses (
+    id TEXT PRIMARY KEY,
+    review_id TEXT NOT NULL UNIQUE,
+    user_id TEXT NOT NULL,
+    comment TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (review_id) REFERENCES reviews(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,15 @@
+-- Helpful / unhelpful votes on reviews, one per user per review
+CREATE TABLE IF NOT EXISTS review_votes (
+    id TEXT PRIMARY KEY,
+    review_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    helpful INTEGER NOT NULL CHECK (helpful IN (0, 1)),
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (review_id) REFERENCES reviews(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE(review_id, user_id)
+);
+
+-- Cached vote counts, kept in sync by the app whenever votes change
+ALTER TABLE reviews ADD COLUMN helpful_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE reviews ADD COLUMN unhelpful_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,17 @@
+-- Hidden reviews stay in the table for moderators but are left out of listings and ratings
+ALTER TABLE reviews ADD COLUMN is_hidden INTEGER NOT NULL DEFAULT 0;
+
+-- User reports of abusive reviews, one per user per review
+CREATE TABLE IF NOT EXISTS review_reports (
+    id TEXT PRIMARY KEY,
+    review_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    reason TEXT NOT NULL CHECK (reason IN ('spam', 'offensive', 'off_topic')),
+    resolved INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (review_id) REFERENCES reviews(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE(review_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_review_reports_pending ON review_reports(resolved, review_id);
@@ -0,0 +1,2 @@
+-- Content filter flags (JSON array) on reviews held for moderation; NULL once reviewed
+ALTER TABLE reviews ADD COLUMN moderation_flags TEXT;
@@ -0,0 +1,4 @@
+-- Lexicon sentiment per review (-1..1; NULL until scored at startup) and per business
+ALTER TABLE reviews ADD COLUMN sentiment REAL;
+ALTER TABLE reviews ADD COLUMN rating_mismatch INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE businesses ADD COLUMN average_sentiment REAL NOT NULL DEFAULT 0.0;
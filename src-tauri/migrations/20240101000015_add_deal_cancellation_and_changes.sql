@@ -0,0 +1,18 @@
+-- Cancelled deals stay inactive whatever their dates say
+ALTER TABLE deals ADD COLUMN cancelled_at TEXT;
+
+-- Audit trail of deal edits, cancellations and deletions. No foreign key to
+-- deals so the history outlives a deleted deal.
+CREATE TABLE IF NOT EXISTS deal_changes (
+    id TEXT PRIMARY KEY,
+    deal_id TEXT NOT NULL,
+    business_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    field TEXT NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    changed_at TEXT NOT NULL,
+    FOREIGN KEY (business_id) REFERENCES businesses(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_deal_changes_business ON deal_changes(business_id, changed_at);
@@ -0,0 +1,29 @@
+-- Redemption limits; NULL max_redemptions means unlimited
+ALTER TABLE deals ADD COLUMN max_redemptions INTEGER CHECK (max_redemptions IS NULL OR max_redemptions >= 1);
+ALTER TABLE deals ADD COLUMN max_redemptions_per_user INTEGER NOT NULL DEFAULT 1 CHECK (max_redemptions_per_user >= 1);
+
+-- Single-use codes issued to one user for deals without a shared code
+CREATE TABLE IF NOT EXISTS deal_codes (
+    code TEXT PRIMARY KEY,
+    deal_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    issued_at TEXT NOT NULL,
+    redeemed_at TEXT,
+    FOREIGN KEY (deal_id) REFERENCES deals(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_deal_codes_deal_user ON deal_codes(deal_id, user_id);
+
+-- Every redemption, with either the shared code or the user's own code
+CREATE TABLE IF NOT EXISTS deal_redemptions (
+    id TEXT PRIMARY KEY,
+    deal_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    code TEXT NOT NULL,
+    redeemed_at TEXT NOT NULL,
+    FOREIGN KEY (deal_id) REFERENCES deals(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_deal_redemptions_deal_user ON deal_redemptions(deal_id, user_id);
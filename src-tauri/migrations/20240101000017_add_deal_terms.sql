@@ -0,0 +1,2 @@
+-- Structured deal terms as a JSON array of DealTerms
+ALTER TABLE deals ADD COLUMN terms TEXT NOT NULL DEFAULT '[]';
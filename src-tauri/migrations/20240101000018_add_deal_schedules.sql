@@ -0,0 +1,2 @@
+-- Optional weekly recurrence as a JSON DealSchedule; NULL runs all day
+ALTER TABLE deals ADD COLUMN schedule TEXT;
@@ -0,0 +1,57 @@
+-- Rebuild the search index keyed on businesses.id instead of the implicit
+-- rowid, which VACUUM is free to renumber
+DROP TRIGGER IF EXISTS businesses_search_insert;
+DROP TRIGGER IF EXISTS businesses_search_update;
+DROP TRIGGER IF EXISTS businesses_search_delete;
+DROP TRIGGER IF EXISTS reviews_search_insert;
+DROP TRIGGER IF EXISTS reviews_search_update;
+DROP TRIGGER IF EXISTS reviews_search_delete;
+DROP TABLE IF EXISTS business_search;
+
+CREATE VIRTUAL TABLE business_search USING fts5(
+    name,
+    category,
+    description,
+    reviews,
+    business_id UNINDEXED,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO business_search (name, category, description, reviews, business_id)
+SELECT b.name, b.category, b.description,
+       COALESCE((SELECT group_concat(r.comment, ' ') FROM reviews r WHERE r.business_id = b.id), ''),
+       b.id
+FROM businesses b;
+
+CREATE TRIGGER businesses_search_insert AFTER INSERT ON businesses BEGIN
+    INSERT INTO business_search (name, category, description, reviews, business_id)
+    VALUES (new.name, new.category, new.description, '', new.id);
+END;
+
+CREATE TRIGGER businesses_search_update AFTER UPDATE OF name, category, description ON businesses BEGIN
+    UPDATE business_search
+    SET name = new.name, category = new.category, description = new.description
+    WHERE business_id = new.id;
+END;
+
+CREATE TRIGGER businesses_search_delete AFTER DELETE ON businesses BEGIN
+    DELETE FROM business_search WHERE business_id = old.id;
+END;
+
+CREATE TRIGGER reviews_search_insert AFTER INSERT ON reviews BEGIN
+    UPDATE business_search
+    SET reviews = COALESCE((SELECT group_concat(comment, ' ') FROM reviews WHERE business_id = new.business_id), '')
+    WHERE business_id = new.business_id;
+END;
+
+CREATE TRIGGER reviews_search_update AFTER UPDATE OF comment ON reviews BEGIN
+    UPDATE business_search
+    SET reviews = COALESCE((SELECT group_concat(comment, ' ') FROM reviews WHERE business_id = new.business_id), '')
+    WHERE business_id = new.business_id;
+END;
+
+CREATE TRIGGER reviews_search_delete AFTER DELETE ON reviews BEGIN
+    UPDATE business_search
+    SET reviews = COALESCE((SELECT group_concat(comment, ' ') FROM reviews WHERE business_id = old.business_id), '')
+    WHERE business_id = old.business_id;
+END;
@@ -0,0 +1,26 @@
+-- Index only visible review text, so hidden reviews stop matching searches
+-- and showing up in snippets, and re-index when a review is hidden or restored
+DROP TRIGGER IF EXISTS reviews_search_insert;
+DROP TRIGGER IF EXISTS reviews_search_update;
+DROP TRIGGER IF EXISTS reviews_search_delete;
+
+CREATE TRIGGER reviews_search_insert AFTER INSERT ON reviews BEGIN
+    UPDATE business_search
+    SET reviews = COALESCE((SELECT group_concat(comment, ' ') FROM reviews WHERE business_id = new.business_id AND is_hidden = 0), '')
+    WHERE business_id = new.business_id;
+END;
+
+CREATE TRIGGER reviews_search_update AFTER UPDATE OF comment, is_hidden ON reviews BEGIN
+    UPDATE business_search
+    SET reviews = COALESCE((SELECT group_concat(comment, ' ') FROM reviews WHERE business_id = new.business_id AND is_hidden = 0), '')
+    WHERE business_id = new.business_id;
+END;
+
+CREATE TRIGGER reviews_search_delete AFTER DELETE ON reviews BEGIN
+    UPDATE business_search
+    SET reviews = COALESCE((SELECT group_concat(comment, ' ') FROM reviews WHERE business_id = old.business_id AND is_hidden = 0), '')
+    WHERE business_id = old.business_id;
+END;
+
+UPDATE business_search
+SET reviews = COALESCE((SELECT group_concat(r.comment, ' ') FROM reviews r WHERE r.business_id = business_search.business_id AND r.is_hidden = 0), '');
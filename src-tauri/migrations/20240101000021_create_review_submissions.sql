@@ -0,0 +1,15 @@
+-- Every review submission, new or edit. Rows outlive the review so deleting
+-- a review can't reset the rate limits.
+CREATE TABLE IF NOT EXISTS review_submissions (
+    user_id TEXT NOT NULL,
+    is_new INTEGER NOT NULL,
+    submitted_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_review_submissions_user ON review_submissions (user_id, submitted_at);
+
+INSERT INTO review_submissions (user_id, is_new, submitted_at)
+SELECT user_id, 1, created_at FROM reviews;
+
+INSERT INTO review_submissions (user_id, is_new, submitted_at)
+SELECT r.user_id, 0, e.edited_at FROM review_edits e JOIN reviews r ON r.id = e.review_id;
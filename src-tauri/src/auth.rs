@@ -0,0 +1,142 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::error::{AppError, AppResult};
+use crate::validation::Validator;
+
+/// How long a sign-in lasts before the user must sign in again
+const SESSION_TTL_HOURS: i64 = 24;
+
+const MIN_PASSWORD_LEN: usize = 8;
+const MAX_PASSWORD_LEN: usize = 128;
+
+/// Hash a password with Argon2id and a random salt (PHC string format)
+pub fn hash_password(password: &str) -> AppResult<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::Internal(format!("Failed to hash password: {}", e)))
+}
+
+/// Check a password against a stored PHC hash
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+        .unwrap_or(false)
+}
+
+/// Password policy for new accounts
+pub fn validate_password(password: &str) -> AppResult<()> {
+    let len = password.chars().count();
+    Validator::new()
+        .check(
+            (MIN_PASSWORD_LEN..=MAX_PASSWORD_LEN).contains(&len),
+            "password",
+            format!("must be between {} and {} characters", MIN_PASSWORD_LEN, MAX_PASSWORD_LEN),
+        )
+        .check(
+            password.chars().any(|c| c.is_alphabetic()) && password.chars().any(|c| !c.is_alphabetic()),
+            "password",
+            "must contain a letter and a number or symbol",
+        )
+        .finish()
+}
+
+/// The signed-in user for this app instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub token: String,
+    pub user_id: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Holds the current session in Tauri managed state, so commands never have
+/// to trust a user id sent by the frontend
+#[derive(Default)]
+pub struct SessionStore {
+    current: Mutex<Option<Session>>,
+}
+
+impl SessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new session for `user_id`, replacing any existing one
+    pub fn sign_in(&self, user_id: &str) -> Session {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+
+        let session = Session {
+            token: bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+            user_id: user_id.to_string(),
+            expires_at: Utc::now() + Duration::hours(SESSION_TTL_HOURS),
+        };
+        *self.current.lock().unwrap() = Some(session.clone());
+        session
+    }
+
+    pub fn sign_out(&self) {
+        *self.current.lock().unwrap() = None;
+    }
+
+    /// The current session, if there is one and it hasn't expired
+    pub fn current(&self) -> Option<Session> {
+        let mut current = self.current.lock().unwrap();
+        if current.as_ref().is_some_and(|s| s.expires_at <= Utc::now()) {
+            *current = None;
+        }
+        current.clone()
+    }
+
+    /// The signed-in user's id, or an error asking them to sign in
+    pub fn user_id(&self) -> AppResult<String> {
+        self.current()
+            .map(|s| s.user_id)
+            .ok_or_else(|| AppError::Unauthorized("Please sign in first".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_are_salted_and_verify() {
+        let first = hash_password("correct horse 1").unwrap();
+        let second = hash_password("correct horse 1").unwrap();
+
+        assert_ne!(first, second);
+        assert!(verify_password("correct horse 1", &first));
+        assert!(!verify_password("wrong horse 1", &first));
+        assert!(!verify_password("correct horse 1", "not a hash"));
+    }
+
+    #[test]
+    fn password_policy() {
+        assert!(validate_password("hunter2!x").is_ok());
+        assert!(validate_password("short1").is_err());
+        assert!(validate_password("onlyletters").is_err());
+    }
+
+    #[test]
+    fn sessions_expire_and_sign_out() {
+        let store = SessionStore::new();
+        assert!(store.user_id().is_err());
+
+        store.sign_in("user-1");
+        assert_eq!(store.user_id().unwrap(), "user-1");
+
+        store.current.lock().unwrap().as_mut().unwrap().expires_at = Utc::now() - Duration::seconds(1);
+        assert!(store.user_id().is_err());
+
+        store.sign_in("user-2");
+        store.sign_out();
+        assert!(store.current().is_none());
+    }
+}
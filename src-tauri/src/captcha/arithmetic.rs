@@ -0,0 +1,77 @@
+use rand::{Rng, RngCore};
+
+use super::{CaptchaPrompt, CaptchaProvider, Puzzle};
+
+/// Multi-step arithmetic such as `(7 × 4) - 9 = ?`
+pub struct ArithmeticProvider;
+
+impl CaptchaProvider for ArithmeticProvider {
+    fn generate(&self, rng: &mut dyn RngCore) -> Puzzle {
+        let a: i64 = rng.gen_range(2..20);
+        let b: i64 = rng.gen_range(2..20);
+        let c: i64 = rng.gen_range(2..20);
+
+        let (first, inner) = match rng.gen_range(0..3) {
+            0 => ("+", a + b),
+            1 => ("-", a - b),
+            _ => ("×", a * b),
+        };
+        let (second, result) = match rng.gen_range(0..3) {
+            0 => ("+", inner + c),
+            1 => ("-", inner - c),
+            _ => ("×", inner * c),
+        };
+
+        Puzzle {
+            prompt: CaptchaPrompt::Text {
+                question: format!("({} {} {}) {} {} = ?", a, first, b, second, c),
+            },
+            secret: result.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Evaluate a generated question so the test doesn't trust the provider's own math
+    fn evaluate(question: &str) -> i64 {
+        let tokens: Vec<&str> = question
+            .trim_end_matches(" = ?")
+            .split([' ', '(', ')'])
+            .filter(|t| !t.is_empty())
+            .collect();
+        let apply = |x: i64, op: &str, y: i64| match op {
+            "+" => x + y,
+            "-" => x - y,
+            "×" => x * y,
+            _ => panic!("unexpected operator {}", op),
+        };
+        let inner = apply(tokens[0].parse().unwrap(), tokens[1], tokens[2].parse().unwrap());
+        apply(inner, tokens[3], tokens[4].parse().unwrap())
+    }
+
+    #[test]
+    fn same_seed_gives_same_puzzle() {
+        let first = ArithmeticProvider.generate(&mut StdRng::seed_from_u64(42));
+        let second = ArithmeticProvider.generate(&mut StdRng::seed_from_u64(42));
+        assert_eq!(first.prompt, second.prompt);
+        assert_eq!(first.secret, second.secret);
+    }
+
+    #[test]
+    fn secret_matches_question() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..200 {
+            let puzzle = ArithmeticProvider.generate(&mut rng);
+            let CaptchaPrompt::Text { question } = &puzzle.prompt else {
+                panic!("expected a text prompt");
+            };
+            assert_eq!(puzzle.secret, evaluate(question).to_string());
+            assert!(ArithmeticProvider.check(&puzzle.secret, &format!(" {} ", puzzle.secret)));
+        }
+    }
+}
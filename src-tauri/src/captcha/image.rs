@@ -0,0 +1,182 @@
+use base64::Engine;
+use rand::{Rng, RngCore};
+
+use super::{CaptchaPrompt, CaptchaProvider, Puzzle};
+
+/// Characters that are hard to confuse with each other once distorted
+const ALPHABET: &[u8] = b"ACDEFHJKLMNPRTUVWXY34679";
+const CODE_LENGTH: usize = 5;
+
+const SCALE: usize = 4;
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+const CELL_WIDTH: usize = GLYPH_WIDTH * SCALE + 8;
+const MARGIN: usize = 12;
+const WIDTH: usize = CELL_WIDTH * CODE_LENGTH + MARGIN * 2;
+const HEIGHT: usize = GLYPH_HEIGHT * SCALE + MARGIN * 2;
+
+/// Locally rendered distorted-text PNG
+pub struct ImageProvider;
+
+impl CaptchaProvider for ImageProvider {
+    fn generate(&self, rng: &mut dyn RngCore) -> Puzzle {
+        let code: String = (0..CODE_LENGTH)
+            .map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())] as char)
+            .collect();
+        let png = encode_png(&render(&code, rng));
+
+        Puzzle {
+            prompt: CaptchaPrompt::Image {
+                question: "Type the characters shown in the image".to_string(),
+                image: format!(
+                    "data:image/png;base64,{}",
+                    base64::engine::general_purpose::STANDARD.encode(png)
+                ),
+            },
+            secret: code,
+        }
+    }
+}
+
+/// Draw the code into a grayscale buffer with jitter, a wave warp and noise
+fn render(code: &str, rng: &mut dyn RngCore) -> Vec<u8> {
+    let mut pixels = vec![255u8; WIDTH * HEIGHT];
+    let amplitude: f32 = rng.gen_range(2.0..4.0);
+    let period: f32 = rng.gen_range(18.0..30.0);
+    let phase: f32 = rng.gen_range(0.0..std::f32::consts::TAU);
+
+    for (i, ch) in code.bytes().enumerate() {
+        let rows = glyph(ch);
+        let x0 = MARGIN + i * CELL_WIDTH + rng.gen_range(0..6);
+        let y0 = MARGIN as i32 + rng.gen_range(-6..=6);
+        let shade: u8 = rng.gen_range(0..80);
+
+        for (gy, row) in rows.iter().enumerate() {
+            for gx in 0..GLYPH_WIDTH {
+                if row & (1 << (GLYPH_WIDTH - 1 - gx)) == 0 {
+                    continue;
+                }
+                for dy in 0..SCALE {
+                    for dx in 0..SCALE {
+                        let x = x0 + gx * SCALE + dx;
+                        let wave = amplitude * ((x as f32 / period) + phase).sin();
+                        let y = y0 + (gy * SCALE + dy) as i32 + wave.round() as i32;
+                        if y >= 0 && (y as usize) < HEIGHT && x < WIDTH {
+                            pixels[y as usize * WIDTH + x] = shade;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Strike-through lines
+    for _ in 0..3 {
+        let mut y = rng.gen_range(0.0..HEIGHT as f32);
+        let slope: f32 = rng.gen_range(-0.3..0.3);
+        for x in 0..WIDTH {
+            if (0.0..HEIGHT as f32).contains(&y) {
+                pixels[y as usize * WIDTH + x] = 90;
+            }
+            y += slope;
+        }
+    }
+
+    // Speckle noise
+    for _ in 0..(WIDTH * HEIGHT / 12) {
+        let index = rng.gen_range(0..pixels.len());
+        pixels[index] = rng.gen_range(0..=255);
+    }
+
+    pixels
+}
+
+fn encode_png(pixels: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, WIDTH as u32, HEIGHT as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    // Writing into a Vec can't fail
+    let mut writer = encoder.write_header().expect("PNG header");
+    writer.write_image_data(pixels).expect("PNG data");
+    writer.finish().expect("PNG finish");
+    out
+}
+
+/// 5x7 bitmap rows for every character in `ALPHABET`
+fn glyph(ch: u8) -> [u8; GLYPH_HEIGHT] {
+    match ch {
+        b'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        b'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        b'D' => [0b11110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11110],
+        b'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        b'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        b'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        b'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        b'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        b'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        b'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        b'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        b'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        b'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        b'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        b'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        b'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        b'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        b'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        b'Y' => [0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0b00100],
+        b'3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        b'4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        b'6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        b'7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        b'9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        _ => [0; GLYPH_HEIGHT],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn decode(prompt: &CaptchaPrompt) -> (png::OutputInfo, Vec<u8>) {
+        let CaptchaPrompt::Image { image, .. } = prompt else {
+            panic!("expected an image prompt");
+        };
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(image.trim_start_matches("data:image/png;base64,"))
+            .unwrap();
+        let mut reader = png::Decoder::new(data.as_slice()).read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        (info, buf)
+    }
+
+    #[test]
+    fn same_seed_gives_same_image() {
+        let first = ImageProvider.generate(&mut StdRng::seed_from_u64(11));
+        let second = ImageProvider.generate(&mut StdRng::seed_from_u64(11));
+        assert_eq!(first.prompt, second.prompt);
+        assert_eq!(first.secret, second.secret);
+    }
+
+    #[test]
+    fn renders_valid_png_for_code() {
+        let puzzle = ImageProvider.generate(&mut StdRng::seed_from_u64(12));
+        assert_eq!(puzzle.secret.len(), CODE_LENGTH);
+        assert!(puzzle.secret.bytes().all(|c| ALPHABET.contains(&c)));
+
+        let (info, pixels) = decode(&puzzle.prompt);
+        assert_eq!((info.width as usize, info.height as usize), (WIDTH, HEIGHT));
+        assert!(pixels.iter().filter(|p| **p < 80).count() > 200);
+    }
+
+    #[test]
+    fn every_character_has_a_glyph() {
+        for ch in ALPHABET {
+            assert!(glyph(*ch).iter().any(|row| *row != 0), "missing glyph {}", *ch as char);
+        }
+    }
+}
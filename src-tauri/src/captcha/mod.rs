@@ -0,0 +1,216 @@
+mod arithmetic;
+mod image;
+mod proof_of_work;
+mod word;
+
+pub use arithmetic::ArithmeticProvider;
+pub use image::ImageProvider;
+pub use proof_of_work::ProofOfWorkProvider;
+pub use word::WordProvider;
+
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use crate::config::{CaptchaConfig, CaptchaKind};
+use crate::error::{AppError, AppResult};
+
+/// How long an issued challenge stays valid
+const CAPTCHA_TTL_MINUTES: i64 = 5;
+
+/// What the frontend shows (or computes) for a challenge
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CaptchaPrompt {
+    Text {
+        question: String,
+    },
+    Image {
+        question: String,
+        /// PNG encoded as a `data:` URL so it can be used as an `<img src>`
+        image: String,
+    },
+    ProofOfWork {
+        question: String,
+        prefix: String,
+        difficulty: u32,
+    },
+}
+
+/// A generated puzzle: the prompt for the user and the secret used to check it
+pub struct Puzzle {
+    pub prompt: CaptchaPrompt,
+    pub secret: String,
+}
+
+/// A source of CAPTCHA puzzles
+pub trait CaptchaProvider: Send + Sync {
+    /// Create a new puzzle using the given RNG
+    fn generate(&self, rng: &mut dyn RngCore) -> Puzzle;
+
+    /// Check a user's answer against the stored secret
+    fn check(&self, secret: &str, answer: &str) -> bool {
+        secret.eq_ignore_ascii_case(answer.trim())
+    }
+}
+
+/// Build the provider selected in the app config
+pub fn provider_from_config(config: &CaptchaConfig) -> Box<dyn CaptchaProvider> {
+    match config.provider {
+        CaptchaKind::Arithmetic => Box::new(ArithmeticProvider),
+        CaptchaKind::Word => Box::new(WordProvider),
+        CaptchaKind::Image => Box::new(ImageProvider),
+        CaptchaKind::ProofOfWork => Box::new(ProofOfWorkProvider::new(config.pow_difficulty)),
+    }
+}
+
+/// Challenge sent to the frontend (the secret never leaves Rust)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CaptchaChallenge {
+    pub id: String,
+    #[serde(flatten)]
+    pub prompt: CaptchaPrompt,
+}
+
+/// Server-side record of an issued challenge
+struct PendingChallenge {
+    secret: String,
+    expires_at: DateTime<Utc>,
+    solved: bool,
+}
+
+/// In-memory registry of issued CAPTCHA challenges
+pub struct CaptchaStore {
+    provider: Box<dyn CaptchaProvider>,
+    rng: Mutex<StdRng>,
+    challenges: Mutex<HashMap<String, PendingChallenge>>,
+}
+
+impl CaptchaStore {
+    pub fn new(provider: Box<dyn CaptchaProvider>) -> Self {
+        Self::with_rng(provider, StdRng::from_entropy())
+    }
+
+    /// Create a store with a fixed RNG, for reproducible puzzles
+    pub fn with_rng(provider: Box<dyn CaptchaProvider>, rng: StdRng) -> Self {
+        Self {
+            provider,
+            rng: Mutex::new(rng),
+            challenges: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Issue a new challenge and remember its secret
+    pub fn issue(&self) -> CaptchaChallenge {
+        let puzzle = self.provider.generate(&mut *self.rng.lock().unwrap());
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        let mut challenges = self.challenges.lock().unwrap();
+        challenges.retain(|_, c| c.expires_at > now);
+        challenges.insert(
+            id.clone(),
+            PendingChallenge {
+                secret: puzzle.secret,
+                expires_at: now + Duration::minutes(CAPTCHA_TTL_MINUTES),
+                solved: false,
+            },
+        );
+
+        CaptchaChallenge {
+            id,
+            prompt: puzzle.prompt,
+        }
+    }
+
+    /// Check an answer; a wrong answer discards the challenge so it can't be brute forced
+    pub fn verify(&self, challenge_id: &str, answer: &str) -> AppResult<bool> {
+        let mut challenges = self.challenges.lock().unwrap();
+        let Some(challenge) = challenges.get_mut(challenge_id) else {
+            return Err(captcha_error("was not found or has already been used"));
+        };
+
+        if challenge.expires_at <= Utc::now() {
+            challenges.remove(challenge_id);
+            return Err(captcha_error("has expired"));
+        }
+
+        if self.provider.check(&challenge.secret, answer) {
+            challenge.solved = true;
+            Ok(true)
+        } else {
+            challenges.remove(challenge_id);
+            Ok(false)
+        }
+    }
+
+    /// Use up a solved challenge; each challenge can only be consumed once
+    pub fn consume(&self, challenge_id: &str) -> AppResult<()> {
+        let mut challenges = self.challenges.lock().unwrap();
+        match challenges.remove(challenge_id) {
+            Some(c) if c.expires_at <= Utc::now() => Err(captcha_error("has expired")),
+            Some(c) if !c.solved => Err(captcha_error("has not been solved")),
+            Some(_) => Ok(()),
+            None => Err(captcha_error("was not found or has already been used")),
+        }
+    }
+}
+
+fn captcha_error(reason: &str) -> AppError {
+    AppError::validation("captcha", reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> CaptchaStore {
+        CaptchaStore::with_rng(Box::new(ArithmeticProvider), StdRng::seed_from_u64(7))
+    }
+
+    #[test]
+    fn solved_challenge_can_be_consumed_once() {
+        let store = store();
+        let challenge = store.issue();
+        let secret = store.challenges.lock().unwrap()[&challenge.id].secret.clone();
+
+        assert!(store.verify(&challenge.id, &secret).unwrap());
+        assert!(store.consume(&challenge.id).is_ok());
+        assert!(store.consume(&challenge.id).is_err());
+    }
+
+    #[test]
+    fn wrong_answer_discards_challenge() {
+        let store = store();
+        let challenge = store.issue();
+
+        assert!(!store.verify(&challenge.id, "not a number").unwrap());
+        assert!(store.verify(&challenge.id, "0").is_err());
+    }
+
+    #[test]
+    fn unsolved_challenge_is_rejected() {
+        let store = store();
+        let challenge = store.issue();
+
+        assert!(store.consume(&challenge.id).is_err());
+    }
+
+    #[test]
+    fn expired_challenge_is_rejected() {
+        let store = store();
+        let challenge = store.issue();
+        let secret = {
+            let mut challenges = store.challenges.lock().unwrap();
+            let pending = challenges.get_mut(&challenge.id).unwrap();
+            pending.expires_at = Utc::now() - Duration::seconds(1);
+            pending.secret.clone()
+        };
+
+        assert!(store.verify(&challenge.id, &secret).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use super::{CaptchaPrompt, CaptchaProvider, Puzzle};
+
+/// Hashcash-style puzzle: find a nonce so that `SHA-256("{prefix}:{nonce}")`
+/// starts with `difficulty` zero bits
+pub struct ProofOfWorkProvider {
+    difficulty: u32,
+}
+
+impl ProofOfWorkProvider {
+    pub fn new(difficulty: u32) -> Self {
+        Self {
+            difficulty: difficulty.min(32),
+        }
+    }
+}
+
+impl CaptchaProvider for ProofOfWorkProvider {
+    fn generate(&self, rng: &mut dyn RngCore) -> Puzzle {
+        let mut bytes = [0u8; 16];
+        rng.fill_bytes(&mut bytes);
+        let prefix: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+        Puzzle {
+            prompt: CaptchaPrompt::ProofOfWork {
+                question: "Verifying your browser, please wait".to_string(),
+                prefix: prefix.clone(),
+                difficulty: self.difficulty,
+            },
+            secret: prefix,
+        }
+    }
+
+    fn check(&self, secret: &str, answer: &str) -> bool {
+        let digest = Sha256::digest(format!("{}:{}", secret, answer.trim()).as_bytes());
+        leading_zero_bits(&digest) >= self.difficulty
+    }
+}
+
+fn leading_zero_bits(bytes: &[u8]) -> u32 {
+    let mut count = 0;
+    for byte in bytes {
+        if *byte == 0 {
+            count += 8;
+        } else {
+            return count + byte.leading_zeros();
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn solve(provider: &ProofOfWorkProvider, prefix: &str) -> String {
+        (0u64..)
+            .map(|n| n.to_string())
+            .find(|nonce| provider.check(prefix, nonce))
+            .unwrap()
+    }
+
+    #[test]
+    fn same_seed_gives_same_prefix() {
+        let provider = ProofOfWorkProvider::new(8);
+        let first = provider.generate(&mut StdRng::seed_from_u64(21));
+        let second = provider.generate(&mut StdRng::seed_from_u64(21));
+        assert_eq!(first.prompt, second.prompt);
+        assert_eq!(first.secret.len(), 32);
+    }
+
+    #[test]
+    fn accepts_valid_work_and_rejects_other_nonces() {
+        let provider = ProofOfWorkProvider::new(8);
+        let puzzle = provider.generate(&mut StdRng::seed_from_u64(22));
+        let nonce = solve(&provider, &puzzle.secret);
+
+        assert!(provider.check(&puzzle.secret, &nonce));
+        assert!(!provider.check(&puzzle.secret, "0xdeadbeef"));
+        assert!(!ProofOfWorkProvider::new(32).check(&puzzle.secret, &nonce));
+    }
+
+    #[test]
+    fn counts_leading_zero_bits() {
+        assert_eq!(leading_zero_bits(&[0x00, 0x0f]), 12);
+        assert_eq!(leading_zero_bits(&[0x80]), 0);
+        assert_eq!(leading_zero_bits(&[0x00, 0x00]), 16);
+    }
+}
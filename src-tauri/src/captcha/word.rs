@@ -0,0 +1,90 @@
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+use super::{CaptchaPrompt, CaptchaProvider, Puzzle};
+
+const WORDS: &[&str] = &[
+    "bakery", "bicycle", "candle", "market", "harbor", "lantern", "meadow", "orchard",
+    "pepper", "quartz", "river", "saddle", "teapot", "umbrella", "velvet", "window",
+    "yogurt", "zipper", "basket", "cobbler", "dolphin", "furnace", "garden", "hammock",
+    "island", "jigsaw", "kettle", "ladder", "mitten", "noodle", "pickle", "rocket",
+];
+
+const ORDINALS: &[&str] = &["first", "second", "third", "fourth", "fifth", "sixth"];
+
+/// Word questions such as `Type the third letter of "harbor"`
+pub struct WordProvider;
+
+impl CaptchaProvider for WordProvider {
+    fn generate(&self, rng: &mut dyn RngCore) -> Puzzle {
+        let word = *WORDS.choose(rng).unwrap();
+
+        let (question, secret) = match rng.gen_range(0..3) {
+            0 => {
+                let index = rng.gen_range(0..ORDINALS.len().min(word.len()));
+                (
+                    format!("Type the {} letter of \"{}\"", ORDINALS[index], word),
+                    word[index..=index].to_string(),
+                )
+            }
+            1 => (
+                format!("Type the last letter of \"{}\"", word),
+                word[word.len() - 1..].to_string(),
+            ),
+            _ => (
+                format!("Type \"{}\" backwards", word),
+                word.chars().rev().collect(),
+            ),
+        };
+
+        Puzzle {
+            prompt: CaptchaPrompt::Text { question },
+            secret,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn same_seed_gives_same_puzzle() {
+        let first = WordProvider.generate(&mut StdRng::seed_from_u64(3));
+        let second = WordProvider.generate(&mut StdRng::seed_from_u64(3));
+        assert_eq!(first.prompt, second.prompt);
+        assert_eq!(first.secret, second.secret);
+    }
+
+    #[test]
+    fn secret_answers_question() {
+        let mut rng = StdRng::seed_from_u64(9);
+        for _ in 0..200 {
+            let puzzle = WordProvider.generate(&mut rng);
+            let CaptchaPrompt::Text { question } = &puzzle.prompt else {
+                panic!("expected a text prompt");
+            };
+            let word = question.split('"').nth(1).unwrap();
+
+            let expected: String = if question.ends_with("backwards") {
+                word.chars().rev().collect()
+            } else if question.contains("last letter") {
+                word.chars().last().unwrap().to_string()
+            } else {
+                let ordinal = question.split(' ').nth(2).unwrap();
+                let index = ORDINALS.iter().position(|o| *o == ordinal).unwrap();
+                word.chars().nth(index).unwrap().to_string()
+            };
+            assert_eq!(puzzle.secret, expected);
+        }
+    }
+
+    #[test]
+    fn answers_are_case_insensitive() {
+        let puzzle = WordProvider.generate(&mut StdRng::seed_from_u64(5));
+        assert!(WordProvider.check(&puzzle.secret, &puzzle.secret.to_uppercase()));
+        assert!(!WordProvider.check(&puzzle.secret, "not it"));
+    }
+}
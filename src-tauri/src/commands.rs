@@ -1,70 +1,772 @@
-// Simplified commands module without database dependencies
+use chrono::{DateTime, Utc};
+use crate::models::*;
+use crate::auth::{hash_password, validate_password, verify_password, SessionStore};
+use crate::captcha::{CaptchaChallenge, CaptchaStore};
+use crate::config::AppConfig;
+use crate::content_filter::ContentFilter;
+use crate::database::AppDatabase;
+use crate::deal_scheduler;
+use crate::discount;
+use crate::error::{AppError, AppResult};
+use crate::insights::{self, InsightCache};
+use crate::permissions::{authorize, Action, Actor};
+use crate::rate_limit::check_review_limits;
+use crate::validation::{normalize_email, Validate, Validator, MAX_COMMENT_LEN};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Clone)]
+pub struct AppState {
+    pub db: Arc<Mutex<AppDatabase>>,
+    pub captcha: Arc<CaptchaStore>,
+    pub sessions: Arc<SessionStore>,
+    pub content_filter: Arc<ContentFilter>,
+    pub insights: Arc<InsightCache>,
+    pub config: Arc<AppConfig>,
+}
+
+/// Load the signed-in user's role and owned businesses for `authorize`
+async fn current_actor(state: &AppState, db: &AppDatabase) -> AppResult<Actor> {
+    let user_id = state.sessions.user_id()?;
+    db.get_actor(&user_id).await
+}
+
+/// Parse an RFC 3339 date sent by the frontend
+fn parse_date(field: &str, value: &str) -> AppResult<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| date.with_timezone(&Utc))
+        .map_err(|e| AppError::validation(field, format!("is not a valid date: {}", e)))
+}
 
 #[tauri::command]
-pub fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
+pub async fn initialize_app(state: tauri::State<'_, AppState>) -> AppResult<()> {
+    // Initialize database using the new API
+    let db = state.db.lock().await;
+    db.initialize().await?;
+    Ok(())
 }
 
-// Mock commands for demonstration purposes
+// Account commands
 #[tauri::command]
-pub async fn create_user(name: String, email: String) -> Result<serde_json::Value, String> {
-    Ok(serde_json::json!({
-        "id": "demo-user-123",
-        "name": name,
-        "email": email,
-        "created_at": chrono::Utc::now().to_rfc3339(),
-        "updated_at": chrono::Utc::now().to_rfc3339()
+pub async fn sign_up(
+    state: tauri::State<'_, AppState>,
+    name: String,
+    email: String,
+    password: String,
+) -> AppResult<User> {
+    // Everyone starts as a customer; an administrator promotes owners with `set_user_role`
+    let user = User::new(name, email).validated()?;
+    validate_password(&password)?;
+    let password_hash = hash_password(&password)?;
+
+    let db = state.db.lock().await;
+    db.create_user(&user, &password_hash).await?;
+    state.sessions.sign_in(&user.id);
+    Ok(user)
+}
+
+#[tauri::command]
+pub async fn sign_in(state: tauri::State<'_, AppState>, email: String, password: String) -> AppResult<User> {
+    let db = state.db.lock().await;
+    let credentials = db.get_user_credentials(&normalize_email(&email)).await?;
+
+    // Same error for an unknown email and a wrong password
+    match credentials {
+        Some((user, Some(hash))) if verify_password(&password, &hash) => {
+            state.sessions.sign_in(&user.id);
+            Ok(user)
+        }
+        _ => Err(AppError::Unauthorized("Invalid email or password".to_string())),
+    }
+}
+
+#[tauri::command]
+pub fn sign_out(state: tauri::State<'_, AppState>) {
+    state.sessions.sign_out();
+}
+
+#[tauri::command]
+pub async fn current_user(state: tauri::State<'_, AppState>) -> AppResult<Option<User>> {
+    let Some(session) = state.sessions.current() else {
+        return Ok(None);
+    };
+    let db = state.db.lock().await;
+    db.get_user_by_id(&session.user_id).await
+}
+
+#[tauri::command]
+pub async fn get_user(state: tauri::State<'_, AppState>, user_id: String) -> AppResult<Option<UserProfile>> {
+    let db = state.db.lock().await;
+    let Some(user) = db.get_user_by_id(&user_id).await? else {
+        return Ok(None);
+    };
+    // Signed-out callers only ever get the public profile
+    let full = match state.sessions.current() {
+        Some(_) => authorize(&current_actor(&state, &db).await?, Action::ViewAccount { user_id: &user.id }).is_ok(),
+        None => false,
+    };
+
+    Ok(Some(if full {
+        UserProfile::Full(user)
+    } else {
+        UserProfile::Public(PublicProfile { id: user.id, name: user.name })
     }))
 }
 
 #[tauri::command]
-pub async fn get_all_businesses() -> Result<Vec<serde_json::Value>, String> {
-    Ok(vec![
-        serde_json::json!({
-            "id": "1",
-            "name": "Tech Haven",
-            "category": "Electronics",
-            "description": "Your local electronics store with the latest gadgets",
-            "address": "123 Main St",
-            "phone": "555-0123",
-            "website": "techhaven.com",
-            "average_rating": 4.5,
-            "review_count": 128,
-            "has_deals": true,
-            "created_at": chrono::Utc::now().to_rfc3339(),
-            "updated_at": chrono::Utc::now().to_rfc3339()
-        }),
-        serde_json::json!({
-            "id": "2",
-            "name": "Cafe Bliss",
-            "category": "Food",
-            "description": "Cozy coffee shop with artisanal blends",
-            "address": "456 Oak Ave",
-            "phone": "555-0456",
-            "website": "cafebliss.com",
-            "average_rating": 4.8,
-            "review_count": 89,
-            "has_deals": false,
-            "created_at": chrono::Utc::now().to_rfc3339(),
-            "updated_at": chrono::Utc::now().to_rfc3339()
-        })
-    ])
-}
-
-#[tauri::command]
-pub async fn generate_sample_data() -> Result<(), String> {
-    println!("Sample data generation completed (mock)");
+pub async fn set_user_role(state: tauri::State<'_, AppState>, user_id: String, role: UserRole) -> AppResult<()> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    authorize(&actor, Action::ManageUsers)?;
+
+    db.set_user_role(&user_id, role).await
+}
+
+#[tauri::command]
+pub async fn assign_business_owner(
+    state: tauri::State<'_, AppState>,
+    business_id: String,
+    user_id: String,
+) -> AppResult<()> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    authorize(&actor, Action::ManageUsers)?;
+
+    db.add_business_owner(&business_id, &user_id).await
+}
+
+// Business commands
+#[tauri::command]
+pub async fn create_business(
+    state: tauri::State<'_, AppState>,
+    name: String,
+    category: String,
+    description: String,
+    address: String,
+    phone: String,
+    website: Option<String>,
+) -> AppResult<Business> {
+    let business = Business::new(name, category, description, address, phone, website).validated()?;
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    authorize(&actor, Action::CreateBusiness)?;
+
+    // Admins add listings on someone's behalf and assign the owner separately
+    let owner_id = (actor.role == UserRole::Owner).then_some(actor.user_id.as_str());
+    db.create_business(&business, owner_id).await?;
+    Ok(business)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn update_business(
+    state: tauri::State<'_, AppState>,
+    business_id: String,
+    name: String,
+    category: String,
+    description: String,
+    address: String,
+    phone: String,
+    website: Option<String>,
+) -> AppResult<Business> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    authorize(&actor, Action::EditBusiness { business_id: &business_id })?;
+
+    let existing = db.get_business_by_id(&business_id).await?
+        .ok_or_else(|| AppError::NotFound("Business".to_string()))?;
+    let business = Business {
+        name,
+        category,
+        description,
+        address,
+        phone,
+        website,
+        updated_at: Utc::now(),
+        ..existing
+    }
+    .validated()?;
+
+    db.update_business(&business).await?;
+    Ok(business)
+}
+
+#[tauri::command]
+pub async fn get_all_businesses(state: tauri::State<'_, AppState>) -> AppResult<Vec<Business>> {
+    let db = state.db.lock().await;
+    db.get_all_businesses().await
+}
+
+#[tauri::command]
+pub async fn get_business_by_id(state: tauri::State<'_, AppState>, business_id: String) -> AppResult<Option<Business>> {
+    let db = state.db.lock().await;
+    db.get_business_by_id(&business_id).await
+}
+
+#[tauri::command]
+pub async fn search_businesses(state: tauri::State<'_, AppState>, query: String) -> AppResult<SearchResponse> {
+    let db = state.db.lock().await;
+    db.search_businesses(&query).await
+}
+
+#[tauri::command]
+pub async fn query_businesses(state: tauri::State<'_, AppState>, query: BusinessQuery) -> AppResult<BusinessPage> {
+    let db = state.db.lock().await;
+    db.query_businesses(&query, state.config.ranking.prior_strength).await
+}
+
+#[tauri::command]
+pub async fn get_recommended_businesses(state: tauri::State<'_, AppState>, limit: Option<u32>) -> AppResult<Vec<Business>> {
+    let db = state.db.lock().await;
+    db.get_top_rated_businesses(limit, state.config.ranking.prior_strength).await
+}
+
+// Review commands
+#[tauri::command]
+pub async fn create_review(
+    state: tauri::State<'_, AppState>,
+    business_id: String,
+    rating: u8,
+    comment: String,
+    captcha_id: String,
+) -> AppResult<Review> {
+    let mut review = Review::new(business_id, state.sessions.user_id()?, rating, comment).validated()?;
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    authorize(&actor, Action::WriteReview { business_id: &review.business_id })?;
+
+    let now = Utc::now();
+    let is_new = db.get_review_by_user(&review.business_id, &review.user_id).await?.is_none();
+    let activity = db.get_review_activity(&review.user_id, now).await?;
+    check_review_limits(&state.config.reviews, &activity, is_new, now)?;
+
+    let previous = db.get_recent_comments(&review.user_id, &review.business_id).await?;
+    let screening = state.content_filter.screen(&review.comment, &previous)?;
+    review.comment = screening.comment.clone();
+
+    state.captcha.consume(&captcha_id)?;
+
+    let review = db.create_review(&review, Some(&screening)).await?;
+    state.insights.invalidate();
+    Ok(review)
+}
+
+#[tauri::command]
+pub async fn update_review(
+    state: tauri::State<'_, AppState>,
+    review_id: String,
+    rating: u8,
+    comment: String,
+) -> AppResult<Review> {
+    Validator::new().rating(rating).comment(&comment).finish()?;
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    let review = db.get_review_by_id(&review_id).await?
+        .ok_or_else(|| AppError::NotFound("Review".to_string()))?;
+    authorize(&actor, Action::EditReview { author_id: &review.user_id })?;
+
+    let now = Utc::now();
+    let activity = db.get_review_activity(&actor.user_id, now).await?;
+    check_review_limits(&state.config.reviews, &activity, false, now)?;
+
+    let previous = db.get_recent_comments(&actor.user_id, &review.business_id).await?;
+    let screening = state.content_filter.screen(comment.trim(), &previous)?;
+
+    let review = db.update_review(&review_id, &actor.user_id, rating, &screening.comment, Some(&screening)).await?;
+    state.insights.invalidate();
+    Ok(review)
+}
+
+#[tauri::command]
+pub async fn delete_review(state: tauri::State<'_, AppState>, review_id: String) -> AppResult<()> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    let review = db.get_review_by_id(&review_id).await?
+        .ok_or_else(|| AppError::NotFound("Review".to_string()))?;
+    authorize(&actor, Action::DeleteReview { author_id: &review.user_id })?;
+
+    db.delete_review(&review_id).await?;
+    state.insights.invalidate();
     Ok(())
 }
 
-// Additional mock commands can be added here as needed
 #[tauri::command]
-pub async fn get_user() -> Result<serde_json::Value, String> {
-    Ok(serde_json::json!({
-        "id": "demo-user-123",
-        "name": "FBLA User",
-        "email": "fbla@example.com",
-        "created_at": chrono::Utc::now().to_rfc3339(),
-        "updated_at": chrono::Utc::now().to_rfc3339()
-    }))
+pub async fn get_review_history(state: tauri::State<'_, AppState>, review_id: String) -> AppResult<Vec<ReviewEdit>> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    let review = db.get_review_by_id(&review_id).await?
+        .ok_or_else(|| AppError::NotFound("Review".to_string()))?;
+    authorize(&actor, Action::ViewReviewHistory { author_id: &review.user_id })?;
+
+    db.get_review_edits(&review.id).await
+}
+
+#[tauri::command]
+pub async fn get_reviews_by_business(
+    state: tauri::State<'_, AppState>,
+    business_id: String,
+    sort: Option<ReviewSort>,
+) -> AppResult<Vec<ReviewWithResponse>> {
+    let db = state.db.lock().await;
+    db.get_reviews_by_business(&business_id, sort.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn get_review_insights(state: tauri::State<'_, AppState>, business_id: String) -> AppResult<ReviewInsights> {
+    if let Some(cached) = state.insights.get(&business_id) {
+        return Ok(cached);
+    }
+
+    let db = state.db.lock().await;
+    db.get_business_by_id(&business_id).await?
+        .ok_or_else(|| AppError::NotFound("Business".to_string()))?;
+    let summary = insights::summarize(&business_id, &db.get_review_texts().await?);
+    state.insights.insert(summary.clone());
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn vote_review(state: tauri::State<'_, AppState>, review_id: String, helpful: bool) -> AppResult<Review> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    let review = db.get_review_by_id(&review_id).await?
+        .ok_or_else(|| AppError::NotFound("Review".to_string()))?;
+    authorize(&actor, Action::VoteOnReview { author_id: &review.user_id })?;
+
+    db.vote_review(&ReviewVote::new(review.id, actor.user_id, helpful)).await
+}
+
+#[tauri::command]
+pub async fn remove_review_vote(state: tauri::State<'_, AppState>, review_id: String) -> AppResult<Review> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    let review = db.get_review_by_id(&review_id).await?
+        .ok_or_else(|| AppError::NotFound("Review".to_string()))?;
+    authorize(&actor, Action::VoteOnReview { author_id: &review.user_id })?;
+
+    db.remove_review_vote(&review.id, &actor.user_id).await
+}
+
+// Moderation commands
+#[tauri::command]
+pub async fn report_review(state: tauri::State<'_, AppState>, review_id: String, reason: ReportReason) -> AppResult<()> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    let review = db.get_review_by_id(&review_id).await?
+        .ok_or_else(|| AppError::NotFound("Review".to_string()))?;
+    authorize(&actor, Action::ReportReview { author_id: &review.user_id })?;
+
+    let report = ReviewReport::new(review.id, actor.user_id, reason);
+    if db.report_review(&report, state.config.moderation.auto_hide_reports).await? {
+        state.insights.invalidate();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_moderation_queue(state: tauri::State<'_, AppState>) -> AppResult<Vec<ModerationItem>> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    authorize(&actor, Action::ModerateReviews)?;
+
+    db.get_moderation_queue().await
+}
+
+#[tauri::command]
+pub async fn hide_review(state: tauri::State<'_, AppState>, review_id: String) -> AppResult<()> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    authorize(&actor, Action::ModerateReviews)?;
+
+    db.moderate_review(&review_id, true).await?;
+    state.insights.invalidate();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn restore_review(state: tauri::State<'_, AppState>, review_id: String) -> AppResult<()> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    authorize(&actor, Action::ModerateReviews)?;
+
+    db.moderate_review(&review_id, false).await?;
+    state.insights.invalidate();
+    Ok(())
+}
+
+// Review response commands
+#[tauri::command]
+pub async fn respond_to_review(
+    state: tauri::State<'_, AppState>,
+    review_id: String,
+    comment: String,
+) -> AppResult<ReviewResponse> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    let review = db.get_review_by_id(&review_id).await?
+        .ok_or_else(|| AppError::NotFound("Review".to_string()))?;
+    authorize(&actor, Action::RespondToReview { business_id: &review.business_id })?;
+
+    let response = ReviewResponse::new(review.id, actor.user_id, comment).validated()?;
+    db.create_review_response(&response).await?;
+    Ok(response)
+}
+
+#[tauri::command]
+pub async fn update_review_response(
+    state: tauri::State<'_, AppState>,
+    review_id: String,
+    comment: String,
+) -> AppResult<ReviewResponse> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    let review = db.get_review_by_id(&review_id).await?
+        .ok_or_else(|| AppError::NotFound("Review".to_string()))?;
+    authorize(&actor, Action::RespondToReview { business_id: &review.business_id })?;
+
+    Validator::new().text("comment", &comment, MAX_COMMENT_LEN).finish()?;
+    db.update_review_response(&review_id, comment.trim()).await
+}
+
+#[tauri::command]
+pub async fn delete_review_response(state: tauri::State<'_, AppState>, review_id: String) -> AppResult<()> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    let review = db.get_review_by_id(&review_id).await?
+        .ok_or_else(|| AppError::NotFound("Review".to_string()))?;
+    authorize(&actor, Action::DeleteResponse { business_id: &review.business_id })?;
+
+    db.delete_review_response(&review_id).await
+}
+
+// Deal commands
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_deal(
+    state: tauri::State<'_, AppState>,
+    business_id: String,
+    title: String,
+    description: String,
+    discount_code: Option<String>,
+    start_date: String,
+    end_date: String,
+    max_redemptions: Option<u32>,
+    max_redemptions_per_user: Option<u32>,
+    terms: Option<Vec<DealTerms>>,
+    schedule: Option<DealSchedule>,
+) -> AppResult<Deal> {
+    let start_date = parse_date("start_date", &start_date)?;
+    let end_date = parse_date("end_date", &end_date)?;
+
+    let deal = Deal::new(business_id, title, description, discount_code, start_date, end_date);
+    let mut deal = Deal {
+        max_redemptions,
+        max_redemptions_per_user: max_redemptions_per_user.unwrap_or(deal.max_redemptions_per_user),
+        terms: terms.unwrap_or_default(),
+        schedule,
+        ..deal
+    }
+    .validated()?;
+    // A new deal inside its dates may still be outside its weekly window
+    deal.is_active = deal.is_active_at(Utc::now());
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    authorize(&actor, Action::ManageDeals { business_id: &deal.business_id })?;
+
+    db.create_deal(&deal).await?;
+    Ok(deal)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn update_deal(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    deal_id: String,
+    title: String,
+    description: String,
+    discount_code: Option<String>,
+    start_date: String,
+    end_date: String,
+    max_redemptions: Option<u32>,
+    max_redemptions_per_user: Option<u32>,
+    terms: Option<Vec<DealTerms>>,
+    schedule: Option<DealSchedule>,
+    clear_discount_code: Option<bool>,
+    clear_max_redemptions: Option<bool>,
+    clear_schedule: Option<bool>,
+) -> AppResult<Deal> {
+    let edit = DealEdit {
+        title,
+        description,
+        start_date: parse_date("start_date", &start_date)?,
+        end_date: parse_date("end_date", &end_date)?,
+        discount_code,
+        max_redemptions,
+        max_redemptions_per_user,
+        terms,
+        schedule,
+        clear_discount_code: clear_discount_code.unwrap_or(false),
+        clear_max_redemptions: clear_max_redemptions.unwrap_or(false),
+        clear_schedule: clear_schedule.unwrap_or(false),
+    };
+    edit.validate()?;
+
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    let existing = db.get_deal_by_id(&deal_id).await?
+        .ok_or_else(|| AppError::NotFound("Deal".to_string()))?;
+    authorize(&actor, Action::ManageDeals { business_id: &existing.business_id })?;
+    if existing.cancelled_at.is_some() {
+        return Err(AppError::Conflict("Cancelled deals can't be edited".to_string()));
+    }
+
+    let deal = existing.edited(edit, Utc::now()).validated()?;
+
+    if let Some(change) = db.update_deal(&deal, &actor.user_id).await? {
+        deal_scheduler::announce(&app, &change);
+    }
+    Ok(deal)
+}
+
+#[tauri::command]
+pub async fn cancel_deal(app: tauri::AppHandle, state: tauri::State<'_, AppState>, deal_id: String) -> AppResult<Deal> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    let deal = db.get_deal_by_id(&deal_id).await?
+        .ok_or_else(|| AppError::NotFound("Deal".to_string()))?;
+    authorize(&actor, Action::ManageDeals { business_id: &deal.business_id })?;
+
+    let (deal, change) = db.cancel_deal(&deal_id, &actor.user_id).await?;
+    deal_scheduler::announce(&app, &change);
+    Ok(deal)
+}
+
+#[tauri::command]
+pub async fn delete_deal(app: tauri::AppHandle, state: tauri::State<'_, AppState>, deal_id: String) -> AppResult<()> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    let deal = db.get_deal_by_id(&deal_id).await?
+        .ok_or_else(|| AppError::NotFound("Deal".to_string()))?;
+    authorize(&actor, Action::ManageDeals { business_id: &deal.business_id })?;
+
+    let change = db.delete_deal(&deal_id, &actor.user_id).await?;
+    deal_scheduler::announce(&app, &change);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn calculate_discount(
+    state: tauri::State<'_, AppState>,
+    deal_id: String,
+    cart_total: f64,
+) -> AppResult<DiscountQuote> {
+    Validator::new()
+        .check(cart_total.is_finite() && cart_total >= 0.0, "cart_total", "must be zero or more")
+        .finish()?;
+    let db = state.db.lock().await;
+    let deal = db.get_deal_by_id(&deal_id).await?
+        .ok_or_else(|| AppError::NotFound("Deal".to_string()))?;
+
+    Ok(discount::calculate_discount(&deal, cart_total))
+}
+
+/// Load a deal the signed-in user may redeem right now
+async fn redeemable_deal(db: &AppDatabase, actor: &Actor, deal_id: &str) -> AppResult<Deal> {
+    let deal = db.get_deal_by_id(deal_id).await?
+        .ok_or_else(|| AppError::NotFound("Deal".to_string()))?;
+    authorize(actor, Action::RedeemDeal { business_id: &deal.business_id })?;
+    if !deal.is_active_at(Utc::now()) {
+        return Err(AppError::Conflict("This deal isn't running right now".to_string()));
+    }
+    Ok(deal)
+}
+
+#[tauri::command]
+pub async fn claim_deal_code(state: tauri::State<'_, AppState>, deal_id: String) -> AppResult<DealCode> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    let deal = redeemable_deal(&db, &actor, &deal_id).await?;
+
+    db.issue_deal_code(&deal, &actor.user_id).await
+}
+
+#[tauri::command]
+pub async fn redeem_deal(state: tauri::State<'_, AppState>, deal_id: String, code: String) -> AppResult<DealRedemption> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    let deal = redeemable_deal(&db, &actor, &deal_id).await?;
+
+    db.redeem_deal(&deal, &actor.user_id, &code).await
+}
+
+#[tauri::command]
+pub async fn get_redemption_report(
+    state: tauri::State<'_, AppState>,
+    business_id: String,
+) -> AppResult<Vec<DealRedemptionReport>> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    authorize(&actor, Action::ManageDeals { business_id: &business_id })?;
+
+    db.get_redemption_report(&business_id).await
+}
+
+#[tauri::command]
+pub async fn get_deal_history(state: tauri::State<'_, AppState>, business_id: String) -> AppResult<Vec<DealChange>> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    authorize(&actor, Action::ManageDeals { business_id: &business_id })?;
+
+    db.get_deal_changes(&business_id).await
+}
+
+#[tauri::command]
+pub async fn get_deals_by_business(state: tauri::State<'_, AppState>, business_id: String) -> AppResult<Vec<Deal>> {
+    let db = state.db.lock().await;
+    db.get_deals_by_business(&business_id).await
+}
+
+#[tauri::command]
+pub async fn get_active_deals(state: tauri::State<'_, AppState>) -> AppResult<Vec<Deal>> {
+    let db = state.db.lock().await;
+    db.get_active_deals().await
+}
+
+// Favorite commands
+#[tauri::command]
+pub async fn add_favorite(state: tauri::State<'_, AppState>, business_id: String) -> AppResult<Favorite> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    authorize(&actor, Action::Favorite)?;
+
+    let favorite = Favorite::new(actor.user_id, business_id).validated()?;
+    db.add_favorite(&favorite).await?;
+    Ok(favorite)
+}
+
+#[tauri::command]
+pub async fn remove_favorite(state: tauri::State<'_, AppState>, business_id: String) -> AppResult<()> {
+    let db = state.db.lock().await;
+    let actor = current_actor(&state, &db).await?;
+    authorize(&actor, Action::Favorite)?;
+
+    db.remove_favorite(&actor.user_id, &business_id).await
+}
+
+#[tauri::command]
+pub async fn get_favorites_by_user(state: tauri::State<'_, AppState>) -> AppResult<Vec<Business>> {
+    let user_id = state.sessions.user_id()?;
+    let db = state.db.lock().await;
+    db.get_favorites_by_user(&user_id).await
+}
+
+#[tauri::command]
+pub async fn is_favorite(state: tauri::State<'_, AppState>, business_id: String) -> AppResult<bool> {
+    let user_id = state.sessions.user_id()?;
+    let db = state.db.lock().await;
+    db.is_favorite(&user_id, &business_id).await
+}
+
+// CAPTCHA commands
+#[tauri::command]
+pub fn generate_captcha(state: tauri::State<'_, AppState>) -> CaptchaChallenge {
+    state.captcha.issue()
+}
+
+#[tauri::command]
+pub fn verify_captcha(
+    state: tauri::State<'_, AppState>,
+    challenge_id: String,
+    answer: String,
+) -> AppResult<bool> {
+    state.captcha.verify(&challenge_id, &answer)
+}
+
+// Sample data generation for demo purposes
+const SAMPLE_PASSWORD: &str = "boost-demo-1";
+
+#[tauri::command]
+pub async fn generate_sample_data(state: tauri::State<'_, AppState>) -> AppResult<()> {
+    use chrono::Duration;
+
+    let db = state.db.lock().await;
+    // The demo accounts have a published password, so outside development
+    // builds only an administrator may create them
+    if !cfg!(debug_assertions) {
+        let actor = current_actor(&state, &db).await?;
+        authorize(&actor, Action::ManageUsers)?;
+    }
+
+    // Create sample users, one per review so each business gets several reviewers.
+    // They all share SAMPLE_PASSWORD so the demo accounts can sign in.
+    let mut users = Vec::new();
+    for (name, email) in [
+        ("Demo User", "demo@example.com"),
+        ("Sam Rivera", "sam@example.com"),
+        ("Priya Patel", "priya@example.com"),
+    ] {
+        let user = User::new(name.to_string(), email.to_string());
+        db.create_user(&user, &hash_password(SAMPLE_PASSWORD)?).await?;
+        users.push(user);
+    }
+
+    // The owner of every sample business
+    let owner = User {
+        role: UserRole::Owner,
+        ..User::new("Olivia Chen".to_string(), "owner@example.com".to_string())
+    };
+    db.create_user(&owner, &hash_password(SAMPLE_PASSWORD)?).await?;
+
+        // Create sample businesses
+        let categories = ["Food", "Retail", "Services", "Entertainment"];
+        let business_names = [
+            "Joe's Pizza", "Tech Gadgets", "Quick Clean", "Movie Theater",
+            "Burger Joint", "Fashion Boutique", "Auto Repair", "Bowling Alley"
+        ];
+
+        for (i, name) in business_names.iter().enumerate() {
+            let category = categories[i % categories.len()].to_string();
+            let business = Business::new(
+                name.to_string(),
+                category.clone(),
+                format!("A great {} business in town", category.to_lowercase()),
+                format!("123 {} St", name.replace(' ', "-").to_lowercase()),
+                format!("(555) 010-{:04}", 1000 + i),
+                Some(format!("https://{}.com", name.replace(|c: char| !c.is_alphanumeric(), "").to_lowercase())),
+            );
+        db.create_business(&business, Some(&owner.id)).await?;
+
+        // Add some reviews
+        for (j, user) in users.iter().enumerate() {
+            let review = Review::new(
+                business.id.clone(),
+                user.id.clone(),
+                (3 + j) as u8, // Ratings from 3 to 5
+                format!("Great {} business! {} stars!", category.to_lowercase(), 3 + j),
+            );
+            db.create_review(&review, None).await?;
+        }
+
+        // Add a deal for some businesses
+        if i % 2 == 0 {
+            let start_date = Utc::now();
+            let end_date = Utc::now() + Duration::days(30);
+            let deal = Deal {
+                terms: vec![DealTerms::PercentOff { percent: 20.0, max_discount: None }],
+                ..Deal::new(
+                    business.id.clone(),
+                    format!("{} Special Deal", name),
+                    format!("Get 20% off at {}!", name),
+                    Some(format!("DEAL{}", i)),
+                    start_date,
+                    end_date,
+                )
+            };
+            db.create_deal(&deal).await?;
+        }
+    }
+    state.insights.invalidate();
+
+    Ok(())
 }
@@ -0,0 +1,156 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// File name of the optional settings file inside the app data directory
+pub const CONFIG_FILE: &str = "config.json";
+
+/// User-tunable settings; every field falls back to its default when missing
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub captcha: CaptchaConfig,
+    pub reviews: ReviewLimits,
+    pub moderation: ModerationConfig,
+    pub content_filter: ContentFilterConfig,
+    pub ranking: RankingConfig,
+}
+
+/// Which CAPTCHA backend to use for review submission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptchaKind {
+    #[default]
+    Arithmetic,
+    Word,
+    Image,
+    ProofOfWork,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptchaConfig {
+    pub provider: CaptchaKind,
+    /// Leading zero bits required by the proof-of-work provider
+    pub pow_difficulty: u32,
+}
+
+impl Default for CaptchaConfig {
+    fn default() -> Self {
+        Self {
+            provider: CaptchaKind::Arithmetic,
+            pow_difficulty: 18,
+        }
+    }
+}
+
+/// Anti-spam limits on review submission
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReviewLimits {
+    /// New reviews a user may post in any one-hour window
+    pub max_per_hour: u32,
+    /// Minimum gap between two submissions (new reviews or edits) by the same user
+    pub cooldown_seconds: i64,
+}
+
+impl Default for ReviewLimits {
+    fn default() -> Self {
+        Self {
+            max_per_hour: 5,
+            cooldown_seconds: 30,
+        }
+    }
+}
+
+/// Thresholds for the review moderation queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModerationConfig {
+    /// Pending reports after which a review is hidden until an admin looks at it
+    pub auto_hide_reports: u32,
+}
+
+impl Default for ModerationConfig {
+    fn default() -> Self {
+        Self { auto_hide_reports: 3 }
+    }
+}
+
+/// What the content filter does when a rule fires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterAction {
+    /// Let the comment through unchanged
+    Allow,
+    /// Refuse the review with a validation error
+    Reject,
+    /// Star out the offending text (or tidy it, for spam)
+    Mask,
+    /// Save the review hidden and queue it for an admin
+    Moderate,
+}
+
+/// Rules for screening review comments before they're saved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContentFilterConfig {
+    /// Words to block; matching ignores case, leetspeak and stretched letters
+    pub blocked_words: Vec<String>,
+    pub profanity: FilterAction,
+    /// Links and phone numbers
+    pub contact_info: FilterAction,
+    /// Repeated characters, all-caps and near-duplicate reviews
+    pub spam: FilterAction,
+    /// Longest run of one character allowed before it counts as spam
+    pub max_repeated_chars: usize,
+    /// Share of uppercase letters that counts as shouting
+    pub caps_ratio: f64,
+    /// Word overlap (0..1) with one of the user's other reviews that counts as a duplicate
+    pub duplicate_similarity: f64,
+}
+
+impl Default for ContentFilterConfig {
+    fn default() -> Self {
+        let blocked_words = [
+            "ass", "asshole", "bastard", "bitch", "crap", "cunt", "damn", "dick", "fuck", "piss", "shit", "slut",
+            "whore",
+        ];
+        Self {
+            blocked_words: blocked_words.iter().map(|w| w.to_string()).collect(),
+            profanity: FilterAction::Mask,
+            contact_info: FilterAction::Reject,
+            spam: FilterAction::Moderate,
+            max_repeated_chars: 4,
+            caps_ratio: 0.7,
+            duplicate_similarity: 0.8,
+        }
+    }
+}
+
+/// How businesses are ranked by rating
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RankingConfig {
+    /// Weight of the site-wide mean in the Bayesian rating, in reviews; 0 ranks by the plain mean
+    pub prior_strength: f64,
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self { prior_strength: 10.0 }
+    }
+}
+
+impl AppConfig {
+    /// Load settings from disk, using defaults if the file doesn't exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        serde_json::from_str(&contents).context("Failed to parse config file")
+    }
+}
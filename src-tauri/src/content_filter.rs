@@ -0,0 +1,484 @@
+use std::collections::HashSet;
+
+use crate::config::{ContentFilterConfig, FilterAction};
+use crate::error::{AppError, AppResult};
+use crate::models::ContentFlag;
+
+/// Top-level domains recognised in bare links like `deals.biz`
+const LINK_TLDS: &[&str] = &["com", "net", "org", "io", "co", "biz", "info", "xyz", "us", "ly", "me", "app", "shop"];
+
+/// Digit group lengths that look like a phone number, e.g. `555-0123` or `1 (555) 123-4567`
+const PHONE_GROUPS: &[&[usize]] = &[&[7], &[10], &[11], &[3, 4], &[3, 3, 4], &[1, 3, 3, 4]];
+
+/// Endings that still count as a blocked word ("fucking", "shitty", "bitches")
+const INFLECTIONS: &[&str] = &["s", "es", "ed", "er", "ers", "ing", "in", "y", "ty", "ter"];
+
+/// Shouting is only judged on comments with at least this many letters
+const MIN_LETTERS_FOR_CAPS: usize = 12;
+
+/// A comment after screening
+#[derive(Debug, Clone, PartialEq)]
+pub struct Screening {
+    /// The comment with masked words, links and numbers replaced
+    pub comment: String,
+    /// Everything the filter noticed, whatever the configured action
+    pub flags: Vec<ContentFlag>,
+    /// Store the review hidden and put it in the moderation queue
+    pub needs_moderation: bool,
+}
+
+/// Offline profanity and spam screening for review comments
+pub struct ContentFilter {
+    config: ContentFilterConfig,
+    /// Blocked words, normalized and run-length encoded
+    blocked: Vec<Vec<(char, usize)>>,
+}
+
+/// A whitespace-separated token as char offsets into the comment, with
+/// surrounding punctuation trimmed off
+struct Token {
+    start: usize,
+    end: usize,
+    /// Lowercased, leetspeak-decoded letters of the token
+    letters: String,
+}
+
+impl ContentFilter {
+    pub fn new(config: ContentFilterConfig) -> Self {
+        let blocked = config
+            .blocked_words
+            .iter()
+            .map(|w| run_lengths(&decode(w)))
+            .filter(|runs| !runs.is_empty())
+            .collect();
+        Self { config, blocked }
+    }
+
+    /// Screen a comment against the word list and spam heuristics. `previous`
+    /// holds the author's other recent comments, for duplicate detection.
+    /// Returns a validation error if a rejecting rule fired.
+    pub fn screen(&self, comment: &str, previous: &[String]) -> AppResult<Screening> {
+        let mut chars: Vec<char> = comment.chars().collect();
+        let tokens = tokenize(&chars);
+        let mut flags = Vec::new();
+        let mut masked = vec![false; chars.len()];
+
+        let profane = self.profane_spans(&tokens);
+        let links = link_spans(&chars, &tokens);
+        let phones = phone_spans(&chars);
+        for (flag, spans) in [
+            (ContentFlag::Profanity, &profane),
+            (ContentFlag::Link, &links),
+            (ContentFlag::PhoneNumber, &phones),
+        ] {
+            if spans.is_empty() {
+                continue;
+            }
+            flags.push(flag);
+            if self.action(flag) == FilterAction::Mask {
+                for &(start, end) in spans {
+                    masked[start..end].iter_mut().for_each(|m| *m = true);
+                }
+            }
+        }
+        for (c, masked) in chars.iter_mut().zip(&masked) {
+            if *masked && !c.is_whitespace() {
+                *c = '*';
+            }
+        }
+
+        if longest_run(&chars) > self.config.max_repeated_chars {
+            flags.push(ContentFlag::RepeatedCharacters);
+        }
+        if is_shouting(comment, self.config.caps_ratio) {
+            flags.push(ContentFlag::Shouting);
+        }
+        if previous.iter().any(|p| word_similarity(comment, p) >= self.config.duplicate_similarity) {
+            flags.push(ContentFlag::Duplicate);
+        }
+
+        if let Some(flag) = flags.iter().find(|f| self.action(**f) == FilterAction::Reject) {
+            return Err(AppError::validation("comment", rejection_reason(*flag)));
+        }
+
+        let mut text: String = chars.into_iter().collect();
+        let masking = |flag| flags.contains(&flag) && self.action(flag) == FilterAction::Mask;
+        if masking(ContentFlag::RepeatedCharacters) {
+            text = collapse_runs(&text, self.config.max_repeated_chars);
+        }
+        if masking(ContentFlag::Shouting) {
+            text = sentence_case(&text);
+        }
+
+        // A duplicate can't be masked, so masking it means sending it to a moderator
+        let needs_moderation = flags.iter().any(|f| match self.action(*f) {
+            FilterAction::Moderate => true,
+            FilterAction::Mask => *f == ContentFlag::Duplicate,
+            _ => false,
+        });
+
+        Ok(Screening {
+            comment: text,
+            flags,
+            needs_moderation,
+        })
+    }
+
+    fn action(&self, flag: ContentFlag) -> FilterAction {
+        match flag {
+            ContentFlag::Profanity => self.config.profanity,
+            ContentFlag::Link | ContentFlag::PhoneNumber => self.config.contact_info,
+            ContentFlag::RepeatedCharacters | ContentFlag::Shouting | ContentFlag::Duplicate => self.config.spam,
+        }
+    }
+
+    /// Char ranges of blocked words, including ones spelled out letter by letter
+    fn profane_spans(&self, tokens: &[Token]) -> Vec<(usize, usize)> {
+        let mut spans = Vec::new();
+        for token in tokens {
+            if self.is_blocked(&token.letters) {
+                spans.push((token.start, token.end));
+            }
+        }
+
+        // "s h i t" or "f-u c k": runs of one-letter tokens read as a single word
+        let mut i = 0;
+        while i < tokens.len() {
+            let run_end = (i..tokens.len()).find(|&j| tokens[j].letters.chars().count() != 1).unwrap_or(tokens.len());
+            for start in i..run_end {
+                for end in (start + 2..=run_end).rev() {
+                    let joined: String = tokens[start..end].iter().map(|t| t.letters.as_str()).collect();
+                    if self.is_blocked(&joined) {
+                        spans.push((tokens[start].start, tokens[end - 1].end));
+                        break;
+                    }
+                }
+            }
+            i = run_end + 1;
+        }
+        spans
+    }
+
+    /// Whether `letters` spells a blocked word, allowing stretched letters
+    /// ("shiiit") and inflections ("fucking")
+    fn is_blocked(&self, letters: &str) -> bool {
+        let runs = run_lengths(letters);
+        self.blocked.iter().any(|word| {
+            if word.len() > runs.len() || !word.iter().zip(&runs).all(|((wc, wn), (c, n))| wc == c && n >= wn) {
+                return false;
+            }
+            let rest: String = runs[word.len()..].iter().map(|(c, n)| c.to_string().repeat(*n)).collect();
+            rest.is_empty() || INFLECTIONS.contains(&rest.as_str())
+        })
+    }
+}
+
+fn rejection_reason(flag: ContentFlag) -> &'static str {
+    match flag {
+        ContentFlag::Profanity => "contains inappropriate language",
+        ContentFlag::Link => "can't include links",
+        ContentFlag::PhoneNumber => "can't include phone numbers",
+        ContentFlag::RepeatedCharacters | ContentFlag::Shouting => "looks like spam",
+        ContentFlag::Duplicate => "is too similar to another review you wrote",
+    }
+}
+
+/// Lowercase and undo common leetspeak substitutions, keeping only letters
+fn decode(word: &str) -> String {
+    word.chars()
+        .flat_map(char::to_lowercase)
+        .filter_map(|c| match c {
+            '0' => Some('o'),
+            '1' | '!' | '|' => Some('i'),
+            '3' => Some('e'),
+            '4' | '@' => Some('a'),
+            '5' | '$' => Some('s'),
+            '7' | '+' => Some('t'),
+            '8' => Some('b'),
+            c if c.is_alphabetic() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+fn run_lengths(text: &str) -> Vec<(char, usize)> {
+    let mut runs: Vec<(char, usize)> = Vec::new();
+    for c in text.chars() {
+        match runs.last_mut() {
+            Some((last, n)) if *last == c => *n += 1,
+            _ => runs.push((c, 1)),
+        }
+    }
+    runs
+}
+
+fn tokenize(chars: &[char]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let mut start = i;
+        while i < chars.len() && !chars[i].is_whitespace() {
+            i += 1;
+        }
+        let mut end = i;
+
+        // Trim sentence punctuation, but keep leading $ and @ which stand in for letters
+        while start < end && "(\"'".contains(chars[start]) {
+            start += 1;
+        }
+        while end > start && ".,!?;:'\")".contains(chars[end - 1]) {
+            end -= 1;
+        }
+        if start < end {
+            let raw: String = chars[start..end].iter().collect();
+            tokens.push(Token {
+                start,
+                end,
+                letters: decode(&raw),
+            });
+        }
+    }
+    tokens
+}
+
+/// Web addresses, including `www.`, bare `name.tld` and "name dot com"
+fn link_spans(chars: &[char], tokens: &[Token]) -> Vec<(usize, usize)> {
+    let raw = |t: &Token| chars[t.start..t.end].iter().collect::<String>().to_lowercase();
+    let is_tld = |t: &Token| LINK_TLDS.contains(&raw(t).as_str());
+
+    let mut spans = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let text = raw(token);
+        let host = text.split(['/', '?', '#']).next().unwrap_or("");
+        let bare_domain = host
+            .rsplit_once('.')
+            .is_some_and(|(name, tld)| !name.is_empty() && LINK_TLDS.contains(&tld));
+
+        if text.contains("://") || text.starts_with("www.") || bare_domain {
+            spans.push((token.start, token.end));
+        } else if text == "dot" && i > 0 && tokens.get(i + 1).is_some_and(is_tld) {
+            spans.push((tokens[i - 1].start, tokens[i + 1].end));
+        }
+    }
+    spans
+}
+
+/// Runs of digits and separators whose digit groups look like a phone number
+fn phone_spans(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if !(chars[i].is_ascii_digit() || chars[i] == '(' || chars[i] == '+') {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && (chars[i].is_ascii_digit() || " -.()+".contains(chars[i])) {
+            i += 1;
+        }
+        let mut end = i;
+        while end > start && !chars[end - 1].is_ascii_digit() {
+            end -= 1;
+        }
+
+        let digits: String = chars[start..end].iter().collect();
+        let groups: Vec<usize> = digits
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|g| !g.is_empty())
+            .map(str::len)
+            .collect();
+        if PHONE_GROUPS.contains(&groups.as_slice()) {
+            spans.push((start, end));
+        }
+    }
+    spans
+}
+
+/// Longest run of one repeated non-space character
+fn longest_run(chars: &[char]) -> usize {
+    let text: String = chars.iter().filter(|c| **c != '*').collect();
+    run_lengths(&text)
+        .into_iter()
+        .filter(|(c, _)| !c.is_whitespace())
+        .map(|(_, n)| n)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Shorten runs longer than `max` (other than masks) to `max` characters
+fn collapse_runs(text: &str, max: usize) -> String {
+    run_lengths(text)
+        .into_iter()
+        .map(|(c, n)| {
+            let keep = if c == '*' || c.is_whitespace() { n } else { n.min(max) };
+            c.to_string().repeat(keep)
+        })
+        .collect()
+}
+
+fn is_shouting(text: &str, caps_ratio: f64) -> bool {
+    let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+    let upper = letters.iter().filter(|c| c.is_uppercase()).count();
+    letters.len() >= MIN_LETTERS_FOR_CAPS && upper as f64 / letters.len() as f64 >= caps_ratio
+}
+
+/// Lowercase everything except the first letter of each sentence
+fn sentence_case(text: &str) -> String {
+    let mut capitalize = true;
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_alphabetic() {
+            if capitalize {
+                out.extend(c.to_uppercase());
+            } else {
+                out.extend(c.to_lowercase());
+            }
+            capitalize = false;
+        } else {
+            out.push(c);
+            if ".!?".contains(c) {
+                capitalize = true;
+            }
+        }
+    }
+    out
+}
+
+/// Jaccard similarity of the two texts' word sets
+fn word_similarity(a: &str, b: &str) -> f64 {
+    let words = |text: &str| -> HashSet<String> {
+        text.split_whitespace()
+            .map(|w| w.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect::<String>())
+            .filter(|w| !w.is_empty())
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / a.union(&b).count() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(profanity: FilterAction, contact_info: FilterAction, spam: FilterAction) -> ContentFilter {
+        ContentFilter::new(ContentFilterConfig {
+            profanity,
+            contact_info,
+            spam,
+            ..Default::default()
+        })
+    }
+
+    fn masking() -> ContentFilter {
+        filter(FilterAction::Mask, FilterAction::Mask, FilterAction::Mask)
+    }
+
+    fn flags(comment: &str) -> Vec<ContentFlag> {
+        masking().screen(comment, &[]).unwrap().flags
+    }
+
+    #[test]
+    fn catches_profanity_bypass_attempts() {
+        for comment in [
+            "This place is shit",
+            "This place is SHIT!",
+            "This place is sh1t",
+            "This place is $h!t",
+            "This place is s.h.i.t",
+            "This place is s h i t",
+            "This place is shiiiiit",
+            "This place is fucking awful",
+            "What a f-u-c-k-i-n-g mess",
+        ] {
+            assert_eq!(flags(comment), [ContentFlag::Profanity], "{}", comment);
+        }
+    }
+
+    #[test]
+    fn leaves_innocent_words_alone() {
+        for comment in [
+            "A classy spot, worth assessing as a date night option",
+            "Staff were helpful and I will pass it on",
+            "I visited in 2023 and again in 2024, 5 stars",
+            "Great value, e.g. the lunch menu",
+        ] {
+            assert_eq!(flags(comment), [], "{}", comment);
+        }
+    }
+
+    #[test]
+    fn masks_in_place() {
+        let screening = masking().screen("Total sh1t service, call 555-0123!", &[]).unwrap();
+        assert_eq!(screening.comment, "Total **** service, call ********!");
+        assert!(!screening.needs_moderation);
+    }
+
+    #[test]
+    fn detects_links_and_phone_numbers() {
+        for comment in [
+            "Better deals at www.example.org",
+            "Better deals at https://example.org/deals",
+            "Better deals at cheapstuff.biz",
+            "Better deals at cheapstuff dot com",
+        ] {
+            assert_eq!(flags(comment), [ContentFlag::Link], "{}", comment);
+        }
+        for comment in ["Call (555) 123-4567 now", "Call 555.0123 now", "Call 1 555 123 4567 now"] {
+            assert_eq!(flags(comment), [ContentFlag::PhoneNumber], "{}", comment);
+        }
+    }
+
+    #[test]
+    fn detects_spam_patterns() {
+        assert_eq!(flags("Sooooooooo good!!!!!!!!"), [ContentFlag::RepeatedCharacters]);
+        assert_eq!(flags("WORST SERVICE I HAVE EVER HAD"), [ContentFlag::Shouting]);
+        assert_eq!(flags("Loved the BBQ at this place"), []);
+
+        let previous = vec!["Best burgers in town, friendly staff!".to_string()];
+        let screening = masking().screen("best burgers in town friendly staff", &previous).unwrap();
+        assert_eq!(screening.flags, [ContentFlag::Duplicate]);
+        assert!(screening.needs_moderation);
+    }
+
+    #[test]
+    fn masking_spam_tidies_the_text() {
+        let screening = masking().screen("THIS IS THE BEST PIZZA!!!!!!!! GO NOW", &[]).unwrap();
+        assert_eq!(screening.comment, "This is the best pizza!!!! Go now");
+    }
+
+    #[test]
+    fn policy_decides_the_outcome() {
+        let comment = "This place is sh1t";
+
+        let rejected = filter(FilterAction::Reject, FilterAction::Mask, FilterAction::Mask).screen(comment, &[]);
+        assert_eq!(rejected, Err(AppError::validation("comment", "contains inappropriate language")));
+
+        let moderated = filter(FilterAction::Moderate, FilterAction::Mask, FilterAction::Mask)
+            .screen(comment, &[])
+            .unwrap();
+        assert!(moderated.needs_moderation);
+        assert_eq!(moderated.comment, comment);
+
+        let allowed = filter(FilterAction::Allow, FilterAction::Mask, FilterAction::Mask)
+            .screen(comment, &[])
+            .unwrap();
+        assert_eq!((allowed.comment.as_str(), allowed.needs_moderation), (comment, false));
+    }
+
+    #[test]
+    fn custom_word_list() {
+        let filter = ContentFilter::new(ContentFilterConfig {
+            blocked_words: vec!["rotten".to_string()],
+            ..Default::default()
+        });
+        assert!(filter.screen("R0tt3n food", &[]).unwrap().flags.contains(&ContentFlag::Profanity));
+        assert!(filter.screen("This is shit", &[]).unwrap().flags.is_empty());
+    }
+}
@@ -1,9 +1,36 @@
-use anyhow::{Context, Result};
-use sqlx::{Row};
-use sqlx::sqlite::SqlitePool as SqlxPool;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{QueryBuilder, Row, Sqlite};
+use sqlx::sqlite::{SqliteConnection, SqlitePool as SqlxPool, SqliteRow};
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::content_filter::Screening;
+use crate::error::{AppError, AppResult, DbContext};
+use crate::insights::ReviewText;
 use crate::models::*;
+use crate::permissions::Actor;
+use crate::ranking::{rank_businesses, RatingPrior};
+use crate::redemption::{check_limits, generate_code, normalize_code, RedemptionCounts};
+use crate::schedule::{is_deal_active_at, next_transition};
+use crate::rate_limit::ReviewActivity;
+use crate::search::{build_fts_query, fuzzy_matches, render_highlight, suggest_correction};
+use crate::sentiment;
+
+/// Maximum number of full-text search results returned
+const SEARCH_LIMIT: i64 = 50;
+
+/// How many of a user's past comments the duplicate check looks at
+const RECENT_COMMENTS_LIMIT: i64 = 20;
+
+/// Page size for `query_businesses` when the caller doesn't pick one
+const DEFAULT_PAGE_SIZE: u32 = 20;
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Businesses returned by `get_top_rated_businesses` when the caller doesn't pick a number
+const DEFAULT_RECOMMENDATIONS: u32 = 6;
+
+/// Attempts at generating a deal code that isn't already taken
+const CODE_ATTEMPTS: usize = 5;
 
 /// Database wrapper that uses SQLx directly
 #[derive(Clone)]
@@ -13,7 +40,7 @@ pub struct AppDatabase {
 
 impl AppDatabase {
     /// Create new database instance using SQLx
-    pub async fn new(db_url: &str) -> Result<Self> {
+    pub async fn new(db_url: &str) -> AppResult<Self> {
         let pool = SqlxPool::connect(db_url).await
             .context("Failed to create SQLx pool")?;
         
@@ -23,7 +50,7 @@ impl AppDatabase {
     }
 
     /// Initialize database with migrations
-    pub async fn initialize(&self) -> Result<()> {
+    pub async fn initialize(&self) -> AppResult<()> {
         // Run migrations using sqlx
         sqlx::migrate!("./migrations").run(&*self.pool).await?;
 
@@ -32,14 +59,16 @@ impl AppDatabase {
 
     // USER OPERATIONS
 
-    /// Create a new user
-    pub async fn create_user(&self, user: &User) -> Result<()> {
+    /// Create a new user with their password hash
+    pub async fn create_user(&self, user: &User, password_hash: &str) -> AppResult<()> {
         sqlx::query(
-            "INSERT INTO users (id, name, email, created_at, updated_at) VALUES ($1, $2, $3, $4, $5)"
+            "INSERT INTO users (id, name, email, role, password_hash, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7)"
         )
         .bind(&user.id)
         .bind(&user.name)
         .bind(&user.email)
+        .bind(user.role.as_str())
+        .bind(password_hash)
         .bind(user.created_at.to_rfc3339())
         .bind(user.updated_at.to_rfc3339())
         .execute(&*self.pool)
@@ -49,10 +78,35 @@ impl AppDatabase {
         Ok(())
     }
 
+    /// Get a user and their password hash by email, for sign-in
+    pub async fn get_user_credentials(&self, email: &str) -> AppResult<Option<(User, Option<String>)>> {
+        let row = sqlx::query(
+            "SELECT id, name, email, role, password_hash, created_at, updated_at FROM users WHERE email = $1"
+        )
+        .bind(email)
+        .fetch_optional(&*self.pool)
+        .await
+        .context("Failed to get user credentials")?;
+
+        if let Some(row) = row {
+            let user = User {
+                id: row.get("id"),
+                name: row.get("name"),
+                email: row.get("email"),
+                role: UserRole::from_db(row.get("role")),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            };
+            Ok(Some((user, row.get("password_hash"))))
+        } else {
+            Ok(None)
+        }
+    }
+
     /// Get user by ID
-    pub async fn get_user_by_id(&self, user_id: &str) -> Result<Option<User>> {
+    pub async fn get_user_by_id(&self, user_id: &str) -> AppResult<Option<User>> {
                 let row = sqlx::query(
-            "SELECT id, name, email, created_at, updated_at FROM users WHERE id = $1"
+            "SELECT id, name, email, role, created_at, updated_at FROM users WHERE id = $1"
         )
         .bind(user_id)
         .fetch_optional(&*self.pool)
@@ -64,6 +118,7 @@ impl AppDatabase {
                 id: row.get("id"),
                 name: row.get("name"),
                 email: row.get("email"),
+                role: UserRole::from_db(row.get("role")),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
             };
@@ -73,11 +128,80 @@ impl AppDatabase {
         }
     }
 
+    /// Change a user's role
+    pub async fn set_user_role(&self, user_id: &str, role: UserRole) -> AppResult<()> {
+        let result = sqlx::query("UPDATE users SET role = $1, updated_at = $2 WHERE id = $3")
+            .bind(role.as_str())
+            .bind(Utc::now().to_rfc3339())
+            .bind(user_id)
+            .execute(&*self.pool)
+            .await
+            .context("Failed to update user role")?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("User".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Load a user's role and owned businesses for permission checks
+    pub async fn get_actor(&self, user_id: &str) -> AppResult<Actor> {
+        let role: String = sqlx::query_scalar("SELECT role FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(&*self.pool)
+            .await
+            .context("Failed to get user role")?
+            .ok_or_else(|| AppError::NotFound("User".to_string()))?;
+
+        let owned_businesses: Vec<String> =
+            sqlx::query_scalar("SELECT business_id FROM business_owners WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_all(&*self.pool)
+                .await
+                .context("Failed to get owned businesses")?;
+
+        Ok(Actor {
+            user_id: user_id.to_string(),
+            role: UserRole::from_db(&role),
+            owned_businesses: owned_businesses.into_iter().collect(),
+        })
+    }
+
+    /// Record `user_id` as an owner of a business, promoting customers to owners
+    pub async fn add_business_owner(&self, business_id: &str, user_id: &str) -> AppResult<()> {
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+        Self::insert_business_owner(&mut tx, business_id, user_id).await?;
+
+        sqlx::query("UPDATE users SET role = 'owner', updated_at = $1 WHERE id = $2 AND role = 'customer'")
+            .bind(Utc::now().to_rfc3339())
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to promote user to owner")?;
+
+        tx.commit().await.context("Failed to commit business owner")?;
+        Ok(())
+    }
+
+    async fn insert_business_owner(conn: &mut SqliteConnection, business_id: &str, user_id: &str) -> AppResult<()> {
+        sqlx::query("INSERT OR IGNORE INTO business_owners (business_id, user_id, created_at) VALUES ($1, $2, $3)")
+            .bind(business_id)
+            .bind(user_id)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&mut *conn)
+            .await
+            .context("Failed to add business owner")?;
+
+        Ok(())
+    }
+
     // BUSINESS OPERATIONS
 
-    /// Create a new business
-    pub async fn create_business(&self, business: &Business) -> Result<()> {
-                sqlx::query(
+    /// Create a new business, optionally recording its owner
+    pub async fn create_business(&self, business: &Business, owner_id: Option<&str>) -> AppResult<()> {
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+
+        sqlx::query(
             "INSERT INTO businesses (id, name, category, description, address, phone, website, average_rating, review_count, has_deals, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)"
         )
         .bind(&business.id)
@@ -92,17 +216,22 @@ impl AppDatabase {
         .bind(business.has_deals as i64)
         .bind(business.created_at.to_rfc3339())
         .bind(business.updated_at.to_rfc3339())
-        .execute(&*self.pool)
+        .execute(&mut *tx)
         .await
         .context("Failed to create business")?;
 
+        if let Some(owner_id) = owner_id {
+            Self::insert_business_owner(&mut tx, &business.id, owner_id).await?;
+        }
+        tx.commit().await.context("Failed to commit business")?;
+
         Ok(())
     }
 
     /// Get all businesses
-    pub async fn get_all_businesses(&self) -> Result<Vec<Business>> {
+    pub async fn get_all_businesses(&self) -> AppResult<Vec<Business>> {
                 let rows = sqlx::query(
-            "SELECT id, name, category, description, address, phone, website, average_rating, review_count, has_deals, created_at, updated_at FROM businesses"
+            "SELECT id, name, category, description, address, phone, website, average_rating, review_count, average_sentiment, has_deals, created_at, updated_at FROM businesses"
         )
         .fetch_all(&*self.pool)
         .await
@@ -120,6 +249,7 @@ impl AppDatabase {
                 website: row.get("website"),
                 average_rating: row.get("average_rating"),
                 review_count: row.get::<i32, _>("review_count") as usize,
+                average_sentiment: row.get("average_sentiment"),
                 has_deals: row.get::<i32, _>("has_deals") != 0,
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
@@ -131,9 +261,9 @@ impl AppDatabase {
     }
 
     /// Get business by ID
-    pub async fn get_business_by_id(&self, business_id: &str) -> Result<Option<Business>> {
+    pub async fn get_business_by_id(&self, business_id: &str) -> AppResult<Option<Business>> {
                 let row = sqlx::query(
-            "SELECT id, name, category, description, address, phone, website, average_rating, review_count, has_deals, created_at, updated_at FROM businesses WHERE id = $1"
+            "SELECT id, name, category, description, address, phone, website, average_rating, review_count, average_sentiment, has_deals, created_at, updated_at FROM businesses WHERE id = $1"
         )
         .bind(business_id)
         .fetch_optional(&*self.pool)
@@ -151,6 +281,7 @@ impl AppDatabase {
                 website: row.get("website"),
                 average_rating: row.get("average_rating"),
                 review_count: row.get::<i32, _>("review_count") as usize,
+                average_sentiment: row.get("average_sentiment"),
                 has_deals: row.get::<i32, _>("has_deals") != 0,
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
@@ -161,18 +292,183 @@ impl AppDatabase {
         }
     }
 
-    /// Search businesses by query
-    pub async fn search_businesses(&self, query: &str) -> Result<Vec<Business>> {
-                let rows = sqlx::query(
-            "SELECT id, name, category, description, address, phone, website, average_rating, review_count, has_deals, created_at, updated_at
-             FROM businesses
-             WHERE name LIKE $1 OR description LIKE $1 OR category LIKE $1"
+    /// Site-wide mean rating of visible reviews, as the prior for weighted ratings
+    pub async fn get_rating_prior(&self, strength: f64) -> AppResult<RatingPrior> {
+        let mean: Option<f64> = sqlx::query_scalar("SELECT AVG(rating) FROM reviews WHERE is_hidden = 0")
+            .fetch_one(&*self.pool)
+            .await
+            .context("Failed to get mean rating")?;
+
+        Ok(RatingPrior { mean: mean.unwrap_or(0.0), strength: strength.max(0.0) })
+    }
+
+    /// Reviewed businesses with the best weighted ratings, for recommendations
+    pub async fn get_top_rated_businesses(&self, limit: Option<u32>, prior_strength: f64) -> AppResult<Vec<Business>> {
+        let prior = self.get_rating_prior(prior_strength).await?;
+        let mut businesses: Vec<Business> = self.get_all_businesses().await?
+            .into_iter()
+            .filter(|b| b.review_count > 0)
+            .collect();
+
+        rank_businesses(&mut businesses, prior);
+        businesses.truncate(limit.unwrap_or(DEFAULT_RECOMMENDATIONS).clamp(1, MAX_PAGE_SIZE) as usize);
+        Ok(businesses)
+    }
+
+    /// Update a business's details (not its rating, review count or deal flag)
+    pub async fn update_business(&self, business: &Business) -> AppResult<()> {
+        let result = sqlx::query(
+            "UPDATE businesses SET name = $1, category = $2, description = $3, address = $4, phone = $5, website = $6, updated_at = $7 WHERE id = $8"
+        )
+        .bind(&business.name)
+        .bind(&business.category)
+        .bind(&business.description)
+        .bind(&business.address)
+        .bind(&business.phone)
+        .bind(&business.website)
+        .bind(business.updated_at.to_rfc3339())
+        .bind(&business.id)
+        .execute(&*self.pool)
+        .await
+        .context("Failed to update business")?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Business".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Search businesses: exact full-text hits first, then typo-tolerant matches
+    /// on names and categories, plus a "did you mean" suggestion
+    pub async fn search_businesses(&self, query: &str) -> AppResult<SearchResponse> {
+        let mut results = self.full_text_search(query).await?;
+        let businesses = self.get_all_businesses().await?;
+
+        let exact_ids: Vec<&str> = results.iter().map(|r| r.business.id.as_str()).collect();
+        let fuzzy: Vec<SearchResult> = fuzzy_matches(query, &businesses, &exact_ids)
+            .into_iter()
+            .take(SEARCH_LIMIT as usize)
+            .map(|(business, score)| SearchResult {
+                name_highlight: render_highlight(&business.name),
+                snippet: String::new(),
+                business: business.clone(),
+                score,
+                match_kind: MatchKind::Fuzzy,
+            })
+            .collect();
+        results.extend(fuzzy);
+
+        Ok(SearchResponse {
+            results,
+            did_you_mean: suggest_correction(query, &businesses),
+        })
+    }
+
+    /// Full-text search over businesses and their reviews, best matches first
+    async fn full_text_search(&self, query: &str) -> AppResult<Vec<SearchResult>> {
+        let Some(fts_query) = build_fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let rows = sqlx::query(
+            "SELECT b.id, b.name, b.category, b.description, b.address, b.phone, b.website, b.average_rating, b.review_count, b.average_sentiment, b.has_deals, b.created_at, b.updated_at,
+                    bm25(business_search, 10.0, 5.0, 2.0, 1.0) AS rank,
+                    highlight(business_search, 0, char(1), char(2)) AS name_highlight,
+                    snippet(business_search, -1, char(1), char(2), '…', 12) AS snippet
+             FROM business_search
+             JOIN businesses b ON b.id = business_search.business_id
+             WHERE business_search MATCH $1
+             ORDER BY rank
+             LIMIT $2"
         )
-        .bind(format!("%{}%", query))
+        .bind(fts_query)
+        .bind(SEARCH_LIMIT)
         .fetch_all(&*self.pool)
         .await
         .context("Failed to search businesses")?;
 
+        let mut results = Vec::new();
+        for row in rows {
+            let business = Business {
+                id: row.get("id"),
+                name: row.get("name"),
+                category: row.get("category"),
+                description: row.get("description"),
+                address: row.get("address"),
+                phone: row.get("phone"),
+                website: row.get("website"),
+                average_rating: row.get("average_rating"),
+                review_count: row.get::<i32, _>("review_count") as usize,
+                average_sentiment: row.get("average_sentiment"),
+                has_deals: row.get::<i32, _>("has_deals") != 0,
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            };
+            results.push(SearchResult {
+                business,
+                score: -row.get::<f64, _>("rank"),
+                name_highlight: render_highlight(row.get("name_highlight")),
+                snippet: render_highlight(row.get("snippet")),
+                match_kind: MatchKind::Exact,
+            });
+        }
+
+        Ok(results)
+    }
+
+    /// Filter, sort and paginate businesses in SQL
+    pub async fn query_businesses(&self, query: &BusinessQuery, prior_strength: f64) -> AppResult<BusinessPage> {
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        let fts_query = match query.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            None => None,
+            Some(search) => match build_fts_query(search) {
+                Some(fts_query) => Some(fts_query),
+                // Text like "!!!" has no words to match, so nothing matches it
+                None => return Ok(BusinessPage { businesses: Vec::new(), total: 0, offset: query.offset, limit }),
+            },
+        };
+
+        let mut count = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM businesses b");
+        push_business_filters(&mut count, query, fts_query.as_deref());
+        let total: i64 = count
+            .build_query_scalar()
+            .fetch_one(&*self.pool)
+            .await
+            .context("Failed to count businesses")?;
+
+        let mut select = QueryBuilder::<Sqlite>::new(
+            "SELECT b.id, b.name, b.category, b.description, b.address, b.phone, b.website, b.average_rating, b.review_count, b.average_sentiment, b.has_deals, b.created_at, b.updated_at FROM businesses b"
+        );
+        push_business_filters(&mut select, query, fts_query.as_deref());
+
+        let direction = match query.direction {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+        select.push(" ORDER BY ");
+        match query.sort {
+            BusinessSort::ReviewCount => select.push("b.review_count"),
+            BusinessSort::Name => select.push("b.name COLLATE NOCASE"),
+            BusinessSort::Newest => select.push("b.created_at"),
+            // bm25 is lower for better matches, so flip it to make DESC mean "most relevant first"
+            BusinessSort::Relevance if fts_query.is_some() => select.push("-s.rank"),
+            BusinessSort::Rating | BusinessSort::Relevance => {
+                let prior = self.get_rating_prior(prior_strength).await?;
+                push_weighted_rating(&mut select, prior);
+                // Same tie-break as ranking::rank_businesses: more reviews first
+                select.push(format!(" {}, b.review_count", direction))
+            }
+        };
+        select.push(format!(" {}, b.id {}", direction, direction));
+        select.push(" LIMIT ").push_bind(limit as i64);
+        select.push(" OFFSET ").push_bind(query.offset as i64);
+
+        let rows = select
+            .build()
+            .fetch_all(&*self.pool)
+            .await
+            .context("Failed to query businesses")?;
+
         let mut businesses = Vec::new();
         for row in rows {
             let business = Business {
@@ -185,6 +481,7 @@ impl AppDatabase {
                 website: row.get("website"),
                 average_rating: row.get("average_rating"),
                 review_count: row.get::<i32, _>("review_count") as usize,
+                average_sentiment: row.get("average_sentiment"),
                 has_deals: row.get::<i32, _>("has_deals") != 0,
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
@@ -192,243 +489,1233 @@ impl AppDatabase {
             businesses.push(business);
         }
 
-        Ok(businesses)
+        Ok(BusinessPage {
+            businesses,
+            total: total as u64,
+            offset: query.offset,
+            limit,
+        })
     }
 
     // REVIEW OPERATIONS
 
-    /// Create a new review
-    pub async fn create_review(&self, review: &Review) -> Result<()> {
-                sqlx::query(
-            "INSERT INTO reviews (id, business_id, user_id, rating, comment, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7)"
+    /// Create a review, or edit the user's existing review of the same business.
+    /// A screening that needs moderation stores it hidden and flagged from the start.
+    pub async fn create_review(&self, review: &Review, screening: Option<&Screening>) -> AppResult<Review> {
+        if let Some(existing) = self.get_review_by_user(&review.business_id, &review.user_id).await? {
+            return self.update_review(&existing.id, &review.user_id, review.rating, &review.comment, screening).await;
+        }
+
+        let mut review = review.clone();
+        score_sentiment(&mut review);
+        let held_flags = held_flags(screening)?;
+        review.is_hidden = held_flags.is_some();
+
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+
+        sqlx::query(
+            "INSERT INTO reviews (id, business_id, user_id, rating, comment, sentiment, rating_mismatch, is_hidden, moderation_flags, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"
         )
         .bind(&review.id)
         .bind(&review.business_id)
         .bind(&review.user_id)
         .bind(review.rating as i64)
         .bind(&review.comment)
+        .bind(review.sentiment)
+        .bind(review.rating_mismatch as i64)
+        .bind(review.is_hidden as i64)
+        .bind(&held_flags)
         .bind(review.created_at.to_rfc3339())
         .bind(review.updated_at.to_rfc3339())
-        .execute(&*self.pool)
+        .execute(&mut *tx)
         .await
         .context("Failed to create review")?;
+        Self::record_submission(&mut tx, &review.user_id, true, review.created_at).await?;
 
         // Update business rating
-        self.update_business_rating(&review.business_id).await?;
+        Self::update_business_rating(&mut tx, &review.business_id).await?;
+        tx.commit().await.context("Failed to commit review")?;
 
-        Ok(())
+        Ok(review)
     }
 
-    /// Get reviews by business ID
-    pub async fn get_reviews_by_business(&self, business_id: &str) -> Result<Vec<Review>> {
-                let rows = sqlx::query(
-            "SELECT id, business_id, user_id, rating, comment, created_at, updated_at FROM reviews WHERE business_id = $1"
+    /// Get review by ID
+    pub async fn get_review_by_id(&self, review_id: &str) -> AppResult<Option<Review>> {
+        let row = sqlx::query(
+            "SELECT id, business_id, user_id, rating, comment, helpful_count, unhelpful_count, is_hidden, sentiment, rating_mismatch, created_at, updated_at FROM reviews WHERE id = $1"
         )
-        .bind(business_id)
-        .fetch_all(&*self.pool)
+        .bind(review_id)
+        .fetch_optional(&*self.pool)
         .await
-        .context("Failed to get reviews by business")?;
+        .context("Failed to get review")?;
 
-        let mut reviews = Vec::new();
-        for row in rows {
+        if let Some(row) = row {
             let review = Review {
                 id: row.get("id"),
                 business_id: row.get("business_id"),
                 user_id: row.get("user_id"),
                 rating: row.get("rating"),
                 comment: row.get("comment"),
+                helpful_count: row.get::<i32, _>("helpful_count") as u32,
+                unhelpful_count: row.get::<i32, _>("unhelpful_count") as u32,
+                is_hidden: row.get::<i32, _>("is_hidden") != 0,
+                sentiment: row.get::<Option<f32>, _>("sentiment").unwrap_or(0.0),
+                rating_mismatch: row.get::<i32, _>("rating_mismatch") != 0,
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
             };
-            reviews.push(review);
+            Ok(Some(review))
+        } else {
+            Ok(None)
         }
-
-        Ok(reviews)
     }
 
-    /// Update business average rating based on reviews
-    async fn update_business_rating(&self, business_id: &str) -> Result<()> {
-        // Get all reviews for the business
-        let reviews = self.get_reviews_by_business(business_id).await?;
-
-        if reviews.is_empty() {
-            // No reviews, set rating to 0
-                        sqlx::query(
-                "UPDATE businesses SET average_rating = 0.0, review_count = 0 WHERE id = $1"
-            )
-            .bind(business_id)
-            .execute(&*self.pool)
-            .await
-            .context("Failed to update business rating")?;
-        } else {
-            // Calculate average rating
-            let total_rating: u32 = reviews.iter().map(|r| r.rating as u32).sum();
-            let average_rating = total_rating as f32 / reviews.len() as f32;
-
-                        sqlx::query(
-                "UPDATE businesses SET average_rating = $1, review_count = $2 WHERE id = $3"
-            )
-            .bind(average_rating)
-            .bind(reviews.len() as i64)
-            .bind(business_id)
-            .execute(&*self.pool)
-            .await
-            .context("Failed to update business rating")?;
+    /// Edit a review owned by `user_id`, keeping the previous version in the edit history.
+    /// A screening that needs moderation hides and flags it in the same transaction.
+    pub async fn update_review(
+        &self,
+        review_id: &str,
+        user_id: &str,
+        rating: u8,
+        comment: &str,
+        screening: Option<&Screening>,
+    ) -> AppResult<Review> {
+        let mut review = self.get_review_by_id(review_id).await?
+            .ok_or_else(|| AppError::NotFound("Review".to_string()))?;
+        if review.user_id != user_id {
+            return Err(AppError::Forbidden("You can only edit your own reviews".to_string()));
         }
 
-        Ok(())
-    }
+        let edit = ReviewEdit::new(&review);
+        review.rating = rating;
+        review.comment = comment.to_string();
+        review.updated_at = edit.edited_at;
+        score_sentiment(&mut review);
+        let held_flags = held_flags(screening)?;
 
-    // DEAL OPERATIONS
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
 
-    /// Create a new deal
-    pub async fn create_deal(&self, deal: &Deal) -> Result<()> {
-                sqlx::query(
-            "INSERT INTO deals (id, business_id, title, description, discount_code, start_date, end_date, is_active, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"
+        sqlx::query(
+            "INSERT INTO review_edits (id, review_id, previous_rating, previous_comment, edited_at) VALUES ($1, $2, $3, $4, $5)"
         )
-        .bind(&deal.id)
-        .bind(&deal.business_id)
-        .bind(&deal.title)
-        .bind(&deal.description)
-        .bind(&deal.discount_code)
-        .bind(deal.start_date.to_rfc3339())
-        .bind(deal.end_date.to_rfc3339())
-        .bind(deal.is_active as i64)
-        .bind(deal.created_at.to_rfc3339())
-        .bind(deal.updated_at.to_rfc3339())
-        .execute(&*self.pool)
+        .bind(&edit.id)
+        .bind(&edit.review_id)
+        .bind(edit.previous_rating as i64)
+        .bind(&edit.previous_comment)
+        .bind(edit.edited_at.to_rfc3339())
+        .execute(&mut *tx)
         .await
-        .context("Failed to create deal")?;
+        .context("Failed to record review edit")?;
 
-        // Update business has_deals flag
-                sqlx::query(
-            "UPDATE businesses SET has_deals = 1 WHERE id = $1"
+        sqlx::query(
+            "UPDATE reviews SET rating = $1, comment = $2, sentiment = $3, rating_mismatch = $4, updated_at = $5 WHERE id = $6"
         )
-        .bind(&deal.business_id)
-        .execute(&*self.pool)
+        .bind(review.rating as i64)
+        .bind(&review.comment)
+        .bind(review.sentiment)
+        .bind(review.rating_mismatch as i64)
+        .bind(review.updated_at.to_rfc3339())
+        .bind(&review.id)
+        .execute(&mut *tx)
         .await
-        .context("Failed to update business deals flag")?;
+        .context("Failed to update review")?;
+        Self::record_submission(&mut tx, &review.user_id, false, review.updated_at).await?;
+
+        if let Some(flags) = &held_flags {
+            sqlx::query("UPDATE reviews SET is_hidden = 1, moderation_flags = $1 WHERE id = $2")
+                .bind(flags)
+                .bind(&review.id)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to flag review")?;
+            review.is_hidden = true;
+        }
+
+        Self::update_business_rating(&mut tx, &review.business_id).await?;
+        tx.commit().await.context("Failed to commit review update")?;
+
+        Ok(review)
+    }
+
+    /// Delete a review; callers check permissions first
+    pub async fn delete_review(&self, review_id: &str) -> AppResult<()> {
+        let review = self.get_review_by_id(review_id).await?
+            .ok_or_else(|| AppError::NotFound("Review".to_string()))?;
+
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+
+        sqlx::query("DELETE FROM reviews WHERE id = $1")
+            .bind(&review.id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete review")?;
+
+        Self::update_business_rating(&mut tx, &review.business_id).await?;
+        tx.commit().await.context("Failed to commit review deletion")?;
 
         Ok(())
     }
 
-    /// Get deals by business ID
-    pub async fn get_deals_by_business(&self, business_id: &str) -> Result<Vec<Deal>> {
-                let rows = sqlx::query(
-            "SELECT id, business_id, title, description, discount_code, start_date, end_date, is_active, created_at, updated_at FROM deals WHERE business_id = $1"
+    /// Get the edit history of a review, newest first
+    pub async fn get_review_edits(&self, review_id: &str) -> AppResult<Vec<ReviewEdit>> {
+        let rows = sqlx::query(
+            "SELECT id, review_id, previous_rating, previous_comment, edited_at FROM review_edits WHERE review_id = $1 ORDER BY edited_at DESC"
         )
-        .bind(business_id)
+        .bind(review_id)
         .fetch_all(&*self.pool)
         .await
-        .context("Failed to get deals by business")?;
+        .context("Failed to get review edits")?;
 
-        let mut deals = Vec::new();
+        let mut edits = Vec::new();
         for row in rows {
-            let deal = Deal {
+            let edit = ReviewEdit {
                 id: row.get("id"),
-                business_id: row.get("business_id"),
-                title: row.get("title"),
-                description: row.get("description"),
-                discount_code: row.get("discount_code"),
-                start_date: row.get("start_date"),
-                end_date: row.get("end_date"),
-                is_active: row.get("is_active"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
+                review_id: row.get("review_id"),
+                previous_rating: row.get("previous_rating"),
+                previous_comment: row.get("previous_comment"),
+                edited_at: row.get("edited_at"),
             };
-            deals.push(deal);
+            edits.push(edit);
         }
 
-        Ok(deals)
+        Ok(edits)
     }
 
-    /// Get active deals
-    pub async fn get_active_deals(&self) -> Result<Vec<Deal>> {
-                let rows = sqlx::query(
-            "SELECT id, business_id, title, description, discount_code, start_date, end_date, is_active, created_at, updated_at FROM deals WHERE is_active = 1"
+    /// Get a user's review of a business, if they have written one
+    pub async fn get_review_by_user(&self, business_id: &str, user_id: &str) -> AppResult<Option<Review>> {
+        let row = sqlx::query(
+            "SELECT id, business_id, user_id, rating, comment, helpful_count, unhelpful_count, is_hidden, sentiment, rating_mismatch, created_at, updated_at FROM reviews WHERE business_id = $1 AND user_id = $2"
         )
-        .fetch_all(&*self.pool)
+        .bind(business_id)
+        .bind(user_id)
+        .fetch_optional(&*self.pool)
         .await
-        .context("Failed to get active deals")?;
+        .context("Failed to get review by user")?;
 
-        let mut deals = Vec::new();
-        for row in rows {
-            let deal = Deal {
+        if let Some(row) = row {
+            let review = Review {
                 id: row.get("id"),
                 business_id: row.get("business_id"),
-                title: row.get("title"),
-                description: row.get("description"),
-                discount_code: row.get("discount_code"),
-                start_date: row.get("start_date"),
-                end_date: row.get("end_date"),
-                is_active: row.get("is_active"),
+                user_id: row.get("user_id"),
+                rating: row.get("rating"),
+                comment: row.get("comment"),
+                helpful_count: row.get::<i32, _>("helpful_count") as u32,
+                unhelpful_count: row.get::<i32, _>("unhelpful_count") as u32,
+                is_hidden: row.get::<i32, _>("is_hidden") != 0,
+                sentiment: row.get::<Option<f32>, _>("sentiment").unwrap_or(0.0),
+                rating_mismatch: row.get::<i32, _>("rating_mismatch") != 0,
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
             };
-            deals.push(deal);
+            Ok(Some(review))
+        } else {
+            Ok(None)
         }
-
-        Ok(deals)
     }
 
-    // FAVORITE OPERATIONS
-
-    /// Add a business to favorites
-    pub async fn add_favorite(&self, favorite: &Favorite) -> Result<()> {
-                sqlx::query(
-            "INSERT INTO favorites (id, user_id, business_id, created_at) VALUES ($1, $2, $3, $4)"
+    /// Summarize a user's recent review activity for rate limiting, counting
+    /// reviews that have since been deleted
+    pub async fn get_review_activity(&self, user_id: &str, now: DateTime<Utc>) -> AppResult<ReviewActivity> {
+        let hour_ago = (now - Duration::hours(1)).to_rfc3339();
+        let row = sqlx::query(
+            "SELECT
+                (SELECT COUNT(*) FROM review_submissions WHERE user_id = $1 AND is_new = 1 AND submitted_at >= $2) AS created_last_hour,
+                (SELECT MIN(submitted_at) FROM review_submissions WHERE user_id = $1 AND is_new = 1 AND submitted_at >= $2) AS oldest_last_hour,
+                (SELECT MAX(submitted_at) FROM review_submissions WHERE user_id = $1) AS last_submission"
         )
-        .bind(&favorite.id)
-        .bind(&favorite.user_id)
-        .bind(&favorite.business_id)
-        .bind(favorite.created_at.to_rfc3339())
-        .execute(&*self.pool)
+        .bind(user_id)
+        .bind(hour_ago)
+        .fetch_one(&*self.pool)
         .await
-        .context("Failed to add favorite")?;
+        .context("Failed to get review activity")?;
 
-        Ok(())
+        let parse = |value: Option<String>| {
+            value
+                .and_then(|v| DateTime::parse_from_rfc3339(&v).ok())
+                .map(|v| v.with_timezone(&Utc))
+        };
+
+        Ok(ReviewActivity {
+            created_last_hour: row.get::<i64, _>("created_last_hour") as u32,
+            oldest_last_hour: parse(row.get("oldest_last_hour")),
+            last_submission: parse(row.get("last_submission")),
+        })
     }
 
-    /// Remove a business from favorites
-    pub async fn remove_favorite(&self, user_id: &str, business_id: &str) -> Result<()> {
-                sqlx::query(
-            "DELETE FROM favorites WHERE user_id = $1 AND business_id = $2"
+    /// A user's most recent review comments on other businesses, for duplicate detection
+    pub async fn get_recent_comments(&self, user_id: &str, exclude_business_id: &str) -> AppResult<Vec<String>> {
+        sqlx::query_scalar(
+            "SELECT comment FROM reviews WHERE user_id = $1 AND business_id != $2 ORDER BY created_at DESC LIMIT $3"
         )
         .bind(user_id)
-        .bind(business_id)
-        .execute(&*self.pool)
+        .bind(exclude_business_id)
+        .bind(RECENT_COMMENTS_LIMIT)
+        .fetch_all(&*self.pool)
         .await
-        .context("Failed to remove favorite")?;
-
-        Ok(())
+        .context("Failed to get recent comments")
     }
 
-    /// Check if a business is favorited by a user
-    pub async fn is_favorite(&self, user_id: &str, business_id: &str) -> Result<bool> {
-                let row = sqlx::query(
-            "SELECT 1 FROM favorites WHERE user_id = $1 AND business_id = $2"
-        )
-        .bind(user_id)
-        .bind(business_id)
-        .fetch_optional(&*self.pool)
-        .await
-        .context("Failed to check favorite")?;
+    /// Comments of every visible review, the corpus for keyword insights
+    pub async fn get_review_texts(&self) -> AppResult<Vec<ReviewText>> {
+        let rows = sqlx::query("SELECT business_id, comment FROM reviews WHERE is_hidden = 0")
+            .fetch_all(&*self.pool)
+            .await
+            .context("Failed to get review comments")?;
 
-        Ok(row.is_some())
+        Ok(rows
+            .iter()
+            .map(|row| ReviewText {
+                business_id: row.get("business_id"),
+                comment: row.get("comment"),
+            })
+            .collect())
     }
 
-    /// Get favorites by user ID
-    pub async fn get_favorites_by_user(&self, user_id: &str) -> Result<Vec<Business>> {
-                let rows = sqlx::query(
-            "SELECT b.id, b.name, b.category, b.description, b.address, b.phone, b.website, b.average_rating, b.review_count, b.has_deals, b.created_at, b.updated_at
-             FROM favorites f
-             JOIN businesses b ON f.business_id = b.id
-             WHERE f.user_id = $1"
-        )
-        .bind(user_id)
-        .fetch_all(&*self.pool)
-        .await
-        .context("Failed to get favorites by user")?;
+    /// Get visible reviews for a business in the given order, each with the owner's reply if there is one
+    pub async fn get_reviews_by_business(
+        &self,
+        business_id: &str,
+        sort: ReviewSort,
+    ) -> AppResult<Vec<ReviewWithResponse>> {
+        let order = match sort {
+            ReviewSort::Newest => "r.created_at DESC",
+            ReviewSort::Oldest => "r.created_at ASC",
+            ReviewSort::Highest => "r.rating DESC, r.created_at DESC",
+            ReviewSort::Lowest => "r.rating ASC, r.created_at DESC",
+            ReviewSort::MostHelpful => {
+                "r.helpful_count - r.unhelpful_count DESC, r.helpful_count DESC, r.created_at DESC"
+            }
+        };
+        let sql = format!(
+            "SELECT r.id, r.business_id, r.user_id, r.rating, r.comment, r.helpful_count, r.unhelpful_count, r.is_hidden, r.sentiment, r.rating_mismatch, r.created_at, r.updated_at,
+                    rr.id AS response_id, rr.user_id AS response_user_id, rr.comment AS response_comment,
+                    rr.created_at AS response_created_at, rr.updated_at AS response_updated_at
+             FROM reviews r
+             LEFT JOIN review_responses rr ON rr.review_id = r.id
+             WHERE r.business_id = $1 AND r.is_hidden = 0
+             ORDER BY {}, r.id",
+            order
+        );
+
+        let rows = sqlx::query(&sql)
+            .bind(business_id)
+            .fetch_all(&*self.pool)
+            .await
+            .context("Failed to get reviews by business")?;
+
+        let mut reviews = Vec::new();
+        for row in rows {
+            let review = Review {
+                id: row.get("id"),
+                business_id: row.get("business_id"),
+                user_id: row.get("user_id"),
+                rating: row.get("rating"),
+                comment: row.get("comment"),
+                helpful_count: row.get::<i32, _>("helpful_count") as u32,
+                unhelpful_count: row.get::<i32, _>("unhelpful_count") as u32,
+                is_hidden: row.get::<i32, _>("is_hidden") != 0,
+                sentiment: row.get::<Option<f32>, _>("sentiment").unwrap_or(0.0),
+                rating_mismatch: row.get::<i32, _>("rating_mismatch") != 0,
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            };
+            let response = row.get::<Option<String>, _>("response_id").map(|id| ReviewResponse {
+                id,
+                review_id: review.id.clone(),
+                user_id: row.get("response_user_id"),
+                comment: row.get("response_comment"),
+                created_at: row.get("response_created_at"),
+                updated_at: row.get("response_updated_at"),
+            });
+            reviews.push(ReviewWithResponse { review, response });
+        }
+
+        Ok(reviews)
+    }
+
+    // REVIEW VOTE OPERATIONS
+
+    /// Record a vote, replacing the user's earlier vote on the review, and return the updated review
+    pub async fn vote_review(&self, vote: &ReviewVote) -> AppResult<Review> {
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+
+        sqlx::query(
+            "INSERT INTO review_votes (id, review_id, user_id, helpful, created_at) VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT(review_id, user_id) DO UPDATE SET helpful = excluded.helpful, created_at = excluded.created_at"
+        )
+        .bind(&vote.id)
+        .bind(&vote.review_id)
+        .bind(&vote.user_id)
+        .bind(vote.helpful as i64)
+        .bind(vote.created_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await
+        .context("Failed to record vote")?;
+
+        Self::update_vote_counts(&mut tx, &vote.review_id).await?;
+        tx.commit().await.context("Failed to commit vote")?;
+
+        self.get_review_by_id(&vote.review_id).await?
+            .ok_or_else(|| AppError::NotFound("Review".to_string()))
+    }
+
+    /// Withdraw a user's vote on a review and return the updated review
+    pub async fn remove_review_vote(&self, review_id: &str, user_id: &str) -> AppResult<Review> {
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+
+        sqlx::query("DELETE FROM review_votes WHERE review_id = $1 AND user_id = $2")
+            .bind(review_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to remove vote")?;
+
+        Self::update_vote_counts(&mut tx, review_id).await?;
+        tx.commit().await.context("Failed to commit vote removal")?;
+
+        self.get_review_by_id(review_id).await?
+            .ok_or_else(|| AppError::NotFound("Review".to_string()))
+    }
+
+    /// Refresh the cached vote counts on a review
+    async fn update_vote_counts(conn: &mut SqliteConnection, review_id: &str) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE reviews SET
+                helpful_count = (SELECT COUNT(*) FROM review_votes WHERE review_id = $1 AND helpful = 1),
+                unhelpful_count = (SELECT COUNT(*) FROM review_votes WHERE review_id = $1 AND helpful = 0)
+             WHERE id = $1"
+        )
+        .bind(review_id)
+        .execute(&mut *conn)
+        .await
+        .context("Failed to update vote counts")?;
+
+        Ok(())
+    }
+
+    // MODERATION OPERATIONS
+
+    /// File a report and hide the review once it has `auto_hide_reports` pending reports;
+    /// returns whether the review is now hidden
+    pub async fn report_review(&self, report: &ReviewReport, auto_hide_reports: u32) -> AppResult<bool> {
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+
+        sqlx::query(
+            "INSERT INTO review_reports (id, review_id, user_id, reason, resolved, created_at) VALUES ($1, $2, $3, $4, $5, $6)"
+        )
+        .bind(&report.id)
+        .bind(&report.review_id)
+        .bind(&report.user_id)
+        .bind(report.reason.as_str())
+        .bind(report.resolved as i64)
+        .bind(report.created_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await
+        .context("Failed to report review")?;
+
+        let pending: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM review_reports WHERE review_id = $1 AND resolved = 0")
+            .bind(&report.review_id)
+            .fetch_one(&mut *tx)
+            .await
+            .context("Failed to count reports")?;
+
+        let hide = pending >= auto_hide_reports as i64;
+        if hide {
+            Self::set_hidden(&mut tx, &report.review_id, true).await?;
+        }
+        tx.commit().await.context("Failed to commit report")?;
+
+        Ok(hide)
+    }
+
+    /// Reviews with unresolved reports or content filter flags, most reported first
+    pub async fn get_moderation_queue(&self) -> AppResult<Vec<ModerationItem>> {
+        let rows = sqlx::query(
+            "SELECT r.id, r.business_id, r.user_id, r.rating, r.comment, r.helpful_count, r.unhelpful_count, r.is_hidden, r.sentiment, r.rating_mismatch, r.created_at, r.updated_at,
+                    r.moderation_flags, rp.id AS report_id, rp.user_id AS report_user_id, rp.reason, rp.created_at AS reported_at
+             FROM reviews r
+             LEFT JOIN review_reports rp ON rp.review_id = r.id AND rp.resolved = 0
+             WHERE rp.id IS NOT NULL OR r.moderation_flags IS NOT NULL
+             ORDER BY (SELECT COUNT(*) FROM review_reports c WHERE c.review_id = r.id AND c.resolved = 0) DESC,
+                      r.id, rp.created_at"
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to get moderation queue")?;
+
+        let mut queue: Vec<ModerationItem> = Vec::new();
+        for row in rows {
+            let review_id: String = row.get("id");
+            let report = row.get::<Option<String>, _>("report_id").map(|id| ReviewReport {
+                id,
+                review_id: review_id.clone(),
+                user_id: row.get("report_user_id"),
+                reason: ReportReason::from_db(row.get("reason")),
+                resolved: false,
+                created_at: row.get("reported_at"),
+            });
+
+            // Rows arrive grouped by review
+            match queue.last_mut() {
+                Some(item) if item.review.id == review_id => item.reports.extend(report),
+                _ => {
+                    let review = Review {
+                        id: review_id,
+                        business_id: row.get("business_id"),
+                        user_id: row.get("user_id"),
+                        rating: row.get("rating"),
+                        comment: row.get("comment"),
+                        helpful_count: row.get::<i32, _>("helpful_count") as u32,
+                        unhelpful_count: row.get::<i32, _>("unhelpful_count") as u32,
+                        is_hidden: row.get::<i32, _>("is_hidden") != 0,
+                        sentiment: row.get::<Option<f32>, _>("sentiment").unwrap_or(0.0),
+                        rating_mismatch: row.get::<i32, _>("rating_mismatch") != 0,
+                        created_at: row.get("created_at"),
+                        updated_at: row.get("updated_at"),
+                    };
+                    let flags = row
+                        .get::<Option<String>, _>("moderation_flags")
+                        .and_then(|json| serde_json::from_str(&json).ok())
+                        .unwrap_or_default();
+                    queue.push(ModerationItem {
+                        review,
+                        reports: report.into_iter().collect(),
+                        flags,
+                    });
+                }
+            }
+        }
+
+        Ok(queue)
+    }
+
+    /// Hide or restore a review and clear its pending reports and flags
+    pub async fn moderate_review(&self, review_id: &str, hidden: bool) -> AppResult<()> {
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+        Self::set_hidden(&mut tx, review_id, hidden).await?;
+
+        sqlx::query("UPDATE reviews SET moderation_flags = NULL WHERE id = $1")
+            .bind(review_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to clear review flags")?;
+
+        sqlx::query("UPDATE review_reports SET resolved = 1 WHERE review_id = $1 AND resolved = 0")
+            .bind(review_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to resolve reports")?;
+
+        tx.commit().await.context("Failed to commit moderation")?;
+        Ok(())
+    }
+
+    /// Set a review's hidden flag and refresh its business's rating
+    async fn set_hidden(conn: &mut SqliteConnection, review_id: &str, hidden: bool) -> AppResult<()> {
+        let business_id: String = sqlx::query_scalar("UPDATE reviews SET is_hidden = $1 WHERE id = $2 RETURNING business_id")
+            .bind(hidden as i64)
+            .bind(review_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to update review visibility")?
+            .ok_or_else(|| AppError::NotFound("Review".to_string()))?;
+
+        Self::update_business_rating(conn, &business_id).await
+    }
+
+    // REVIEW RESPONSE OPERATIONS
+
+    /// Create the owner's reply to a review
+    pub async fn create_review_response(&self, response: &ReviewResponse) -> AppResult<()> {
+        sqlx::query(
+            "INSERT INTO review_responses (id, review_id, user_id, comment, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6)"
+        )
+        .bind(&response.id)
+        .bind(&response.review_id)
+        .bind(&response.user_id)
+        .bind(&response.comment)
+        .bind(response.created_at.to_rfc3339())
+        .bind(response.updated_at.to_rfc3339())
+        .execute(&*self.pool)
+        .await
+        .context("Failed to create review response")?;
+
+        Ok(())
+    }
+
+    /// Get the reply to a review
+    pub async fn get_review_response(&self, review_id: &str) -> AppResult<Option<ReviewResponse>> {
+        let row = sqlx::query(
+            "SELECT id, review_id, user_id, comment, created_at, updated_at FROM review_responses WHERE review_id = $1"
+        )
+        .bind(review_id)
+        .fetch_optional(&*self.pool)
+        .await
+        .context("Failed to get review response")?;
+
+        if let Some(row) = row {
+            let response = ReviewResponse {
+                id: row.get("id"),
+                review_id: row.get("review_id"),
+                user_id: row.get("user_id"),
+                comment: row.get("comment"),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            };
+            Ok(Some(response))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Replace the text of a review's reply
+    pub async fn update_review_response(&self, review_id: &str, comment: &str) -> AppResult<ReviewResponse> {
+        let mut response = self.get_review_response(review_id).await?
+            .ok_or_else(|| AppError::NotFound("Reply".to_string()))?;
+        response.comment = comment.to_string();
+        response.updated_at = Utc::now();
+
+        sqlx::query("UPDATE review_responses SET comment = $1, updated_at = $2 WHERE id = $3")
+            .bind(&response.comment)
+            .bind(response.updated_at.to_rfc3339())
+            .bind(&response.id)
+            .execute(&*self.pool)
+            .await
+            .context("Failed to update review response")?;
+
+        Ok(response)
+    }
+
+    /// Delete the reply to a review
+    pub async fn delete_review_response(&self, review_id: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM review_responses WHERE review_id = $1")
+            .bind(review_id)
+            .execute(&*self.pool)
+            .await
+            .context("Failed to delete review response")?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Reply".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Set `has_deals` from the business's active deals, inside an open transaction;
+    /// returns the new value
+    async fn update_business_has_deals(conn: &mut SqliteConnection, business_id: &str) -> AppResult<bool> {
+        sqlx::query_scalar(
+            "UPDATE businesses SET has_deals = EXISTS (SELECT 1 FROM deals WHERE business_id = $1 AND is_active = 1)
+             WHERE id = $1
+             RETURNING has_deals"
+        )
+        .bind(business_id)
+        .fetch_optional(conn)
+        .await
+        .context("Failed to update business deals flag")
+        .map(|flag| flag.unwrap_or(false))
+    }
+
+    /// Earliest start, end or window edge still ahead of `now`, when the scheduler next has work
+    pub async fn next_deal_transition(&self, now: DateTime<Utc>) -> AppResult<Option<DateTime<Utc>>> {
+        let rows = sqlx::query(
+            "SELECT id, business_id, title, description, discount_code, start_date, end_date, is_active, cancelled_at, max_redemptions, max_redemptions_per_user, terms, schedule, created_at, updated_at FROM deals WHERE cancelled_at IS NULL"
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to get deal schedule")?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| next_transition(&deal_from_row(row), now))
+            .min())
+    }
+
+    /// Log a review submission for the rate limiter, inside an open transaction
+    async fn record_submission(conn: &mut SqliteConnection, user_id: &str, is_new: bool, at: DateTime<Utc>) -> AppResult<()> {
+        sqlx::query("INSERT INTO review_submissions (user_id, is_new, submitted_at) VALUES ($1, $2, $3)")
+            .bind(user_id)
+            .bind(is_new as i64)
+            .bind(at.to_rfc3339())
+            .execute(conn)
+            .await
+            .context("Failed to record review submission")?;
+
+        Ok(())
+    }
+
+    /// Recompute a business's cached rating from its visible reviews, inside an open transaction
+    async fn update_business_rating(conn: &mut SqliteConnection, business_id: &str) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE businesses SET
+                average_rating = COALESCE((SELECT AVG(rating) FROM reviews WHERE business_id = $1 AND is_hidden = 0), 0.0),
+                review_count = (SELECT COUNT(*) FROM reviews WHERE business_id = $1 AND is_hidden = 0),
+                average_sentiment = COALESCE((SELECT AVG(sentiment) FROM reviews WHERE business_id = $1 AND is_hidden = 0), 0.0)
+             WHERE id = $1"
+        )
+        .bind(business_id)
+        .execute(conn)
+        .await
+        .context("Failed to update business rating")?;
+
+        Ok(())
+    }
+
+    /// Repair cached ratings that no longer match the reviews table.
+    /// Returns the number of businesses that were corrected.
+    pub async fn recompute_all_ratings(&self) -> AppResult<u64> {
+        let result = sqlx::query(
+            "UPDATE businesses SET average_rating = totals.average, review_count = totals.count, average_sentiment = totals.sentiment
+             FROM (
+                 SELECT b.id AS business_id, COALESCE(AVG(r.rating), 0.0) AS average, COUNT(r.id) AS count,
+                        COALESCE(AVG(r.sentiment), 0.0) AS sentiment
+                 FROM businesses b
+                 LEFT JOIN reviews r ON r.business_id = b.id AND r.is_hidden = 0
+                 GROUP BY b.id
+             ) AS totals
+             WHERE totals.business_id = businesses.id
+               AND (ABS(businesses.average_rating - totals.average) > 1e-6
+                    OR businesses.review_count != totals.count
+                    OR ABS(businesses.average_sentiment - totals.sentiment) > 1e-6)"
+        )
+        .execute(&*self.pool)
+        .await
+        .context("Failed to recompute ratings")?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Score reviews written before sentiment analysis existed.
+    /// Returns the number of reviews scored.
+    pub async fn score_unscored_reviews(&self) -> AppResult<u64> {
+        let rows = sqlx::query("SELECT id, rating, comment FROM reviews WHERE sentiment IS NULL")
+            .fetch_all(&*self.pool)
+            .await
+            .context("Failed to get unscored reviews")?;
+
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+        for row in &rows {
+            let comment: String = row.get("comment");
+            let score = sentiment::score(&comment);
+            sqlx::query("UPDATE reviews SET sentiment = $1, rating_mismatch = $2 WHERE id = $3")
+                .bind(score)
+                .bind(sentiment::is_rating_mismatch(row.get::<i64, _>("rating") as u8, score) as i64)
+                .bind(row.get::<String, _>("id"))
+                .execute(&mut *tx)
+                .await
+                .context("Failed to score review")?;
+        }
+        tx.commit().await.context("Failed to commit review scores")?;
+
+        Ok(rows.len() as u64)
+    }
+
+    // DEAL OPERATIONS
+
+    /// Create a new deal
+    pub async fn create_deal(&self, deal: &Deal) -> AppResult<()> {
+        let mut tx = self.pool.begin().await.context("Failed to begin transaction")?;
+
+        sqlx::query(
+            "INSERT INTO deals (id, business_id, title, description, discount_code, start_date, end_date, is_active, max_redemptions, max_redemptions_per_user, terms, schedule, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)"
+        )
+        .bind(&deal.id)
+        .bind(&deal.business_id)
+        .bind(&deal.title)
+        .bind(&deal.description)
+        .bind(&deal.discount_code)
+        .bind(deal.start_date.to_rfc3339())
+        .bind(deal.end_date.to_rfc3339())
+        .bind(deal.is_active as i64)
+        .bind(deal.max_redemptions.map(|n| n as i64))
+        .bind(deal.max_redemptions_per_user as i64)
+        .bind(terms_json(&deal.terms)?)
+        .bind(schedule_json(deal.schedule.as_ref())?)
+        .bind(deal.created_at.to_rfc3339())
+        .bind(deal.updated_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await
+        .context("Failed to create deal")?;
+
+        Self::update_business_has_deals(&mut tx, &deal.business_id).await?;
+
+        tx.commit().await.context("Failed to commit deal")?;
+        Ok(())
+    }
+
+    /// Get deals by business ID
+    pub async fn get_deals_by_business(&self, business_id: &str) -> AppResult<Vec<Deal>> {
+                let rows = sqlx::query(
+            "SELECT id, business_id, title, description, discount_code, start_date, end_date, is_active, cancelled_at, max_redemptions, max_redemptions_per_user, terms, schedule, created_at, updated_at FROM deals WHERE business_id = $1"
+        )
+        .bind(business_id)
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to get deals by business")?;
+
+        Ok(rows.iter().map(deal_from_row).collect())
+    }
+
+    /// Get deal by ID
+    pub async fn get_deal_by_id(&self, deal_id: &str) -> AppResult<Option<Deal>> {
+        let row = sqlx::query(
+            "SELECT id, business_id, title, description, discount_code, start_date, end_date, is_active, cancelled_at, max_redemptions, max_redemptions_per_user, terms, schedule, created_at, updated_at FROM deals WHERE id = $1"
+        )
+        .bind(deal_id)
+        .fetch_optional(&*self.pool)
+        .await
+        .context("Failed to get deal")?;
+
+        Ok(row.map(|row| deal_from_row(&row)))
+    }
+
+    /// Save an edited deal, recording each changed field against `user_id`
+    /// Returns the state change if the edit started or ended the deal
+    pub async fn update_deal(&self, deal: &Deal, user_id: &str) -> AppResult<Option<DealStateChange>> {
+        let existing = self.get_deal_by_id(&deal.id).await?
+            .ok_or_else(|| AppError::NotFound("Deal".to_string()))?;
+        let changes = DealChange::between(&existing, deal, user_id);
+        if changes.is_empty() {
+            return Ok(None);
+        }
+
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+
+        sqlx::query(
+            "UPDATE deals SET title = $1, description = $2, discount_code = $3, start_date = $4, end_date = $5, is_active = $6,
+                max_redemptions = $7, max_redemptions_per_user = $8, terms = $9, schedule = $10, updated_at = $11
+             WHERE id = $12"
+        )
+        .bind(&deal.title)
+        .bind(&deal.description)
+        .bind(&deal.discount_code)
+        .bind(deal.start_date.to_rfc3339())
+        .bind(deal.end_date.to_rfc3339())
+        .bind(deal.is_active as i64)
+        .bind(deal.max_redemptions.map(|n| n as i64))
+        .bind(deal.max_redemptions_per_user as i64)
+        .bind(terms_json(&deal.terms)?)
+        .bind(schedule_json(deal.schedule.as_ref())?)
+        .bind(deal.updated_at.to_rfc3339())
+        .bind(&deal.id)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to update deal")?;
+
+        for change in &changes {
+            Self::insert_deal_change(&mut tx, change).await?;
+        }
+        let business_has_deals = Self::update_business_has_deals(&mut tx, &deal.business_id).await?;
+        tx.commit().await.context("Failed to commit deal update")?;
+
+        Ok((existing.is_active != deal.is_active).then(|| DealStateChange {
+            deal_id: deal.id.clone(),
+            business_id: deal.business_id.clone(),
+            is_active: deal.is_active,
+            business_has_deals,
+        }))
+    }
+
+    /// End a deal early; it stays inactive whatever its dates say
+    pub async fn cancel_deal(&self, deal_id: &str, user_id: &str) -> AppResult<(Deal, DealStateChange)> {
+        let mut deal = self.get_deal_by_id(deal_id).await?
+            .ok_or_else(|| AppError::NotFound("Deal".to_string()))?;
+        if deal.cancelled_at.is_some() {
+            return Err(AppError::Conflict("Deal is already cancelled".to_string()));
+        }
+
+        let now = Utc::now();
+        deal.cancelled_at = Some(now);
+        deal.is_active = false;
+        deal.updated_at = now;
+        let change = DealChange::new(&deal, user_id, "cancelled_at", None, Some(now.to_rfc3339()));
+
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+
+        sqlx::query("UPDATE deals SET cancelled_at = $1, is_active = 0, updated_at = $1 WHERE id = $2")
+            .bind(now.to_rfc3339())
+            .bind(&deal.id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to cancel deal")?;
+
+        Self::insert_deal_change(&mut tx, &change).await?;
+        let business_has_deals = Self::update_business_has_deals(&mut tx, &deal.business_id).await?;
+        tx.commit().await.context("Failed to commit deal cancellation")?;
+
+        let state = DealStateChange {
+            deal_id: deal.id.clone(),
+            business_id: deal.business_id.clone(),
+            is_active: false,
+            business_has_deals,
+        };
+        Ok((deal, state))
+    }
+
+    /// Delete a deal, keeping a record of who deleted it
+    pub async fn delete_deal(&self, deal_id: &str, user_id: &str) -> AppResult<DealStateChange> {
+        let deal = self.get_deal_by_id(deal_id).await?
+            .ok_or_else(|| AppError::NotFound("Deal".to_string()))?;
+        let change = DealChange::new(&deal, user_id, "deal", Some(deal.title.clone()), None);
+
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+
+        sqlx::query("DELETE FROM deals WHERE id = $1")
+            .bind(&deal.id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete deal")?;
+
+        Self::insert_deal_change(&mut tx, &change).await?;
+        let business_has_deals = Self::update_business_has_deals(&mut tx, &deal.business_id).await?;
+        tx.commit().await.context("Failed to commit deal deletion")?;
+
+        Ok(DealStateChange {
+            deal_id: deal.id,
+            business_id: deal.business_id,
+            is_active: false,
+            business_has_deals,
+        })
+    }
+
+    /// Audit trail of a business's deals, newest first
+    pub async fn get_deal_changes(&self, business_id: &str) -> AppResult<Vec<DealChange>> {
+        let rows = sqlx::query(
+            "SELECT id, deal_id, business_id, user_id, field, old_value, new_value, changed_at FROM deal_changes WHERE business_id = $1 ORDER BY changed_at DESC"
+        )
+        .bind(business_id)
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to get deal changes")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DealChange {
+                id: row.get("id"),
+                deal_id: row.get("deal_id"),
+                business_id: row.get("business_id"),
+                user_id: row.get("user_id"),
+                field: row.get("field"),
+                old_value: row.get("old_value"),
+                new_value: row.get("new_value"),
+                changed_at: row.get("changed_at"),
+            })
+            .collect())
+    }
+
+    async fn insert_deal_change(conn: &mut SqliteConnection, change: &DealChange) -> AppResult<()> {
+        sqlx::query(
+            "INSERT INTO deal_changes (id, deal_id, business_id, user_id, field, old_value, new_value, changed_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+        )
+        .bind(&change.id)
+        .bind(&change.deal_id)
+        .bind(&change.business_id)
+        .bind(&change.user_id)
+        .bind(&change.field)
+        .bind(&change.old_value)
+        .bind(&change.new_value)
+        .bind(change.changed_at.to_rfc3339())
+        .execute(conn)
+        .await
+        .context("Failed to record deal change")?;
+
+        Ok(())
+    }
+
+    /// Give `user_id` a single-use code for a deal without a shared code,
+    /// or return the unused one they already have. Unused codes don't take up
+    /// the deal's `max_redemptions`, so one nobody redeems never blocks anyone
+    /// else; once the deal fills up, leftover codes are refused at redemption.
+    pub async fn issue_deal_code(&self, deal: &Deal, user_id: &str) -> AppResult<DealCode> {
+        if deal.discount_code.is_some() {
+            return Err(AppError::Conflict("This deal uses a shared code".to_string()));
+        }
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+
+        let existing = sqlx::query(
+            "SELECT code, issued_at FROM deal_codes WHERE deal_id = $1 AND user_id = $2 AND redeemed_at IS NULL"
+        )
+        .bind(&deal.id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .context("Failed to get deal code")?;
+        if let Some(row) = existing {
+            return Ok(DealCode {
+                code: row.get("code"),
+                deal_id: deal.id.clone(),
+                user_id: user_id.to_string(),
+                issued_at: row.get("issued_at"),
+                redeemed_at: None,
+            });
+        }
+
+        check_limits(deal, Self::redemption_counts(&mut tx, &deal.id, user_id).await?)?;
+
+        for _ in 0..CODE_ATTEMPTS {
+            let code = DealCode::new(deal.id.clone(), user_id.to_string(), generate_code());
+            let inserted = sqlx::query(
+                "INSERT INTO deal_codes (code, deal_id, user_id, issued_at) VALUES ($1, $2, $3, $4)"
+            )
+            .bind(&code.code)
+            .bind(&code.deal_id)
+            .bind(&code.user_id)
+            .bind(code.issued_at.to_rfc3339())
+            .execute(&mut *tx)
+            .await
+            .context("Failed to issue deal code");
+
+            match inserted {
+                Ok(_) => {
+                    tx.commit().await.context("Failed to commit deal code")?;
+                    return Ok(code);
+                }
+                // Collided with an existing code; roll again
+                Err(AppError::Conflict(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(AppError::Internal("Failed to generate a unique deal code".to_string()))
+    }
+
+    /// Record `user_id` redeeming a deal with its shared code or their own code
+    pub async fn redeem_deal(&self, deal: &Deal, user_id: &str, code: &str) -> AppResult<DealRedemption> {
+        let code = normalize_code(code);
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+        let counts = Self::redemption_counts(&mut tx, &deal.id, user_id).await?;
+
+        if let Some(shared) = &deal.discount_code {
+            if code != *shared {
+                return Err(AppError::validation("code", "doesn't match this deal"));
+            }
+            check_limits(deal, counts)?;
+        } else {
+            let redeemed_at: Option<Option<String>> = sqlx::query_scalar(
+                "SELECT redeemed_at FROM deal_codes WHERE code = $1 AND deal_id = $2 AND user_id = $3"
+            )
+            .bind(&code)
+            .bind(&deal.id)
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .context("Failed to get deal code")?;
+            match redeemed_at {
+                None => return Err(AppError::validation("code", "isn't valid for this deal")),
+                Some(Some(_)) => return Err(AppError::Conflict("This code has already been used".to_string())),
+                Some(None) => {}
+            }
+            // The code being redeemed doesn't count against its own user
+            check_limits(deal, RedemptionCounts { by_user: counts.by_user - 1, ..counts })?;
+        }
+
+        let redemption = DealRedemption::new(deal.id.clone(), user_id.to_string(), code);
+        if deal.discount_code.is_none() {
+            sqlx::query("UPDATE deal_codes SET redeemed_at = $1 WHERE code = $2")
+                .bind(redemption.redeemed_at.to_rfc3339())
+                .bind(&redemption.code)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to mark deal code used")?;
+        }
+        sqlx::query(
+            "INSERT INTO deal_redemptions (id, deal_id, user_id, code, redeemed_at) VALUES ($1, $2, $3, $4, $5)"
+        )
+        .bind(&redemption.id)
+        .bind(&redemption.deal_id)
+        .bind(&redemption.user_id)
+        .bind(&redemption.code)
+        .bind(redemption.redeemed_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await
+        .context("Failed to record redemption")?;
+        tx.commit().await.context("Failed to commit redemption")?;
+
+        Ok(redemption)
+    }
+
+    /// Redemptions made overall, and by `user_id` counting the unused code
+    /// they hold. Other users' unused codes don't count.
+    async fn redemption_counts(
+        conn: &mut SqliteConnection,
+        deal_id: &str,
+        user_id: &str,
+    ) -> AppResult<RedemptionCounts> {
+        let row = sqlx::query(
+            "SELECT
+                (SELECT COUNT(*) FROM deal_redemptions WHERE deal_id = $1) AS redeemed,
+                (SELECT COUNT(*) FROM deal_redemptions WHERE deal_id = $1 AND user_id = $2)
+                    + (SELECT COUNT(*) FROM deal_codes WHERE deal_id = $1 AND user_id = $2 AND redeemed_at IS NULL) AS by_user"
+        )
+        .bind(deal_id)
+        .bind(user_id)
+        .fetch_one(conn)
+        .await
+        .context("Failed to count redemptions")?;
+
+        Ok(RedemptionCounts {
+            total: row.get::<i32, _>("redeemed") as u32,
+            by_user: row.get::<i32, _>("by_user") as u32,
+        })
+    }
+
+    /// Redemption counts for every deal of a business, newest deal first
+    pub async fn get_redemption_report(&self, business_id: &str) -> AppResult<Vec<DealRedemptionReport>> {
+        let rows = sqlx::query(
+            "SELECT d.id, d.title, d.max_redemptions,
+                (SELECT COUNT(*) FROM deal_redemptions r WHERE r.deal_id = d.id) AS redemptions,
+                (SELECT COUNT(DISTINCT r.user_id) FROM deal_redemptions r WHERE r.deal_id = d.id) AS unique_users,
+                (SELECT COUNT(*) FROM deal_codes c WHERE c.deal_id = d.id) AS codes_issued,
+                (SELECT MAX(r.redeemed_at) FROM deal_redemptions r WHERE r.deal_id = d.id) AS last_redeemed_at
+             FROM deals d
+             WHERE d.business_id = $1
+             ORDER BY d.created_at DESC"
+        )
+        .bind(business_id)
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to get redemption report")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DealRedemptionReport {
+                deal_id: row.get("id"),
+                title: row.get("title"),
+                redemptions: row.get::<i32, _>("redemptions") as u32,
+                unique_users: row.get::<i32, _>("unique_users") as u32,
+                codes_issued: row.get::<i32, _>("codes_issued") as u32,
+                max_redemptions: row.get::<Option<i64>, _>("max_redemptions").map(|n| n as u32),
+                last_redeemed_at: row.get("last_redeemed_at"),
+            })
+            .collect())
+    }
+
+    /// Get deals that are running right now
+    pub async fn get_active_deals(&self) -> AppResult<Vec<Deal>> {
+        self.get_deals_active_at(Utc::now()).await
+    }
+
+    /// Get deals running at `instant`. Schedules are evaluated here rather than
+    /// trusting `is_active`, which the scheduler may not have caught up on.
+    pub async fn get_deals_active_at(&self, instant: DateTime<Utc>) -> AppResult<Vec<Deal>> {
+        let rows = sqlx::query(
+            "SELECT id, business_id, title, description, discount_code, start_date, end_date, is_active, cancelled_at, max_redemptions, max_redemptions_per_user, terms, schedule, created_at, updated_at FROM deals WHERE cancelled_at IS NULL"
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to get active deals")?;
+
+        Ok(rows
+            .iter()
+            .map(deal_from_row)
+            .filter(|deal| is_deal_active_at(deal, instant))
+            .collect())
+    }
+
+    /// Activate deals whose start date has passed or whose weekly window has
+    /// opened, deactivate the rest, and return what changed
+    pub async fn sync_deal_states(&self, now: DateTime<Utc>) -> AppResult<Vec<DealStateChange>> {
+        let rows = sqlx::query(
+            "SELECT id, business_id, title, description, discount_code, start_date, end_date, is_active, cancelled_at, max_redemptions, max_redemptions_per_user, terms, schedule, created_at, updated_at FROM deals WHERE cancelled_at IS NULL"
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to get deal schedule")?;
+
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+        let mut changes = Vec::new();
+        for deal in rows.iter().map(deal_from_row) {
+            let is_active = is_deal_active_at(&deal, now);
+            if is_active == deal.is_active {
+                continue;
+            }
+
+            sqlx::query("UPDATE deals SET is_active = $1, updated_at = $2 WHERE id = $3")
+                .bind(is_active as i64)
+                .bind(now.to_rfc3339())
+                .bind(&deal.id)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to update deal state")?;
+
+            changes.push(DealStateChange {
+                deal_id: deal.id,
+                business_id: deal.business_id,
+                is_active,
+                business_has_deals: false,
+            });
+        }
+
+        // Once per business, after all of its deals have been updated
+        let mut has_deals: HashMap<String, bool> = HashMap::new();
+        for change in &mut changes {
+            if !has_deals.contains_key(&change.business_id) {
+                let flag = Self::update_business_has_deals(&mut tx, &change.business_id).await?;
+                has_deals.insert(change.business_id.clone(), flag);
+            }
+            change.business_has_deals = has_deals[&change.business_id];
+        }
+        tx.commit().await.context("Failed to commit deal states")?;
+
+        Ok(changes)
+    }
+
+    // FAVORITE OPERATIONS
+
+    /// Add a business to favorites
+    pub async fn add_favorite(&self, favorite: &Favorite) -> AppResult<()> {
+                sqlx::query(
+            "INSERT INTO favorites (id, user_id, business_id, created_at) VALUES ($1, $2, $3, $4)"
+        )
+        .bind(&favorite.id)
+        .bind(&favorite.user_id)
+        .bind(&favorite.business_id)
+        .bind(favorite.created_at.to_rfc3339())
+        .execute(&*self.pool)
+        .await
+        .context("Failed to add favorite")?;
+
+        Ok(())
+    }
+
+    /// Remove a business from favorites
+    pub async fn remove_favorite(&self, user_id: &str, business_id: &str) -> AppResult<()> {
+                sqlx::query(
+            "DELETE FROM favorites WHERE user_id = $1 AND business_id = $2"
+        )
+        .bind(user_id)
+        .bind(business_id)
+        .execute(&*self.pool)
+        .await
+        .context("Failed to remove favorite")?;
+
+        Ok(())
+    }
+
+    /// Check if a business is favorited by a user
+    pub async fn is_favorite(&self, user_id: &str, business_id: &str) -> AppResult<bool> {
+                let row = sqlx::query(
+            "SELECT 1 FROM favorites WHERE user_id = $1 AND business_id = $2"
+        )
+        .bind(user_id)
+        .bind(business_id)
+        .fetch_optional(&*self.pool)
+        .await
+        .context("Failed to check favorite")?;
+
+        Ok(row.is_some())
+    }
+
+    /// Get favorites by user ID
+    pub async fn get_favorites_by_user(&self, user_id: &str) -> AppResult<Vec<Business>> {
+                let rows = sqlx::query(
+            "SELECT b.id, b.name, b.category, b.description, b.address, b.phone, b.website, b.average_rating, b.review_count, b.average_sentiment, b.has_deals, b.created_at, b.updated_at
+             FROM favorites f
+             JOIN businesses b ON f.business_id = b.id
+             WHERE f.user_id = $1"
+        )
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to get favorites by user")?;
 
         let mut businesses = Vec::new();
         for row in rows {
@@ -442,6 +1729,7 @@ impl AppDatabase {
                 website: row.get("website"),
                 average_rating: row.get("average_rating"),
                 review_count: row.get::<i32, _>("review_count") as usize,
+                average_sentiment: row.get("average_sentiment"),
                 has_deals: row.get::<i32, _>("has_deals") != 0,
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
@@ -451,22 +1739,303 @@ impl AppDatabase {
 
         Ok(businesses)
     }
+}
 
-    // CAPTCHA (kept as is since it doesn't use database)
-    pub fn generate_captcha() -> (String, String) {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
+/// Append the search join and WHERE clause shared by the count and page queries
+fn push_business_filters(qb: &mut QueryBuilder<'_, Sqlite>, query: &BusinessQuery, fts_query: Option<&str>) {
+    if let Some(fts_query) = fts_query {
+        qb.push(" JOIN (SELECT business_id AS sid, bm25(business_search, 10.0, 5.0, 2.0, 1.0) AS rank FROM business_search WHERE business_search MATCH ")
+            .push_bind(fts_query.to_string())
+            .push(") s ON s.sid = b.id");
+    }
 
-        let num1: u8 = rng.gen_range(1..10);
-        let num2: u8 = rng.gen_range(1..10);
-        let operation = if rng.gen_bool(0.5) { "+" } else { "-" };
-        let result = if operation == "+" {
-            num1 + num2
-        } else {
-            num1.abs_diff(num2)
+    qb.push(" WHERE 1 = 1");
+    if let Some(category) = &query.category {
+        qb.push(" AND b.category = ").push_bind(category.clone());
+    }
+    if let Some(min_rating) = query.min_rating {
+        qb.push(" AND b.average_rating >= ").push_bind(min_rating as f64);
+    }
+    if let Some(min_review_count) = query.min_review_count {
+        qb.push(" AND b.review_count >= ").push_bind(min_review_count as i64);
+    }
+    if let Some(has_deals) = query.has_deals {
+        qb.push(" AND b.has_deals = ").push_bind(has_deals as i64);
+    }
+}
+
+/// Score a review's comment and flag it if the text contradicts its rating
+fn score_sentiment(review: &mut Review) {
+    let score = sentiment::score(&review.comment);
+    review.sentiment = score as f32;
+    review.rating_mismatch = sentiment::is_rating_mismatch(review.rating, score);
+}
+
+/// Push the Bayesian weighted rating as a sort expression; the same formula as
+/// `RatingPrior::weighted_rating`
+fn push_weighted_rating<'q, 'args>(
+    qb: &'q mut QueryBuilder<'args, Sqlite>,
+    prior: RatingPrior,
+) -> &'q mut QueryBuilder<'args, Sqlite> {
+    qb.push("((")
+        .push_bind(prior.strength * prior.mean)
+        .push(" + b.average_rating * b.review_count) / (")
+        .push_bind(prior.strength)
+        .push(" + b.review_count))")
+}
+
+/// The content filter flags to store when a screening holds a review for moderation
+fn held_flags(screening: Option<&Screening>) -> AppResult<Option<String>> {
+    screening
+        .filter(|s| s.needs_moderation)
+        .map(|s| serde_json::to_string(&s.flags))
+        .transpose()
+        .map_err(|e| AppError::Internal(format!("Failed to encode moderation flags: {}", e)))
+}
+
+fn terms_json(terms: &[DealTerms]) -> AppResult<String> {
+    serde_json::to_string(terms).map_err(|e| AppError::Internal(format!("Failed to encode deal terms: {}", e)))
+}
+
+fn schedule_json(schedule: Option<&DealSchedule>) -> AppResult<Option<String>> {
+    schedule
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| AppError::Internal(format!("Failed to encode deal schedule: {}", e)))
+}
+
+/// Map a row selected with every `deals` column
+fn deal_from_row(row: &SqliteRow) -> Deal {
+    Deal {
+        id: row.get("id"),
+        business_id: row.get("business_id"),
+        title: row.get("title"),
+        description: row.get("description"),
+        discount_code: row.get("discount_code"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+        is_active: row.get("is_active"),
+        cancelled_at: row.get("cancelled_at"),
+        max_redemptions: row.get::<Option<i64>, _>("max_redemptions").map(|n| n as u32),
+        max_redemptions_per_user: row.get::<i64, _>("max_redemptions_per_user") as u32,
+        terms: serde_json::from_str(row.get("terms")).unwrap_or_default(),
+        schedule: row.get::<Option<&str>, _>("schedule").and_then(|s| serde_json::from_str(s).ok()),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ReviewLimits;
+    use crate::rate_limit::{check_review_limits, RateLimitError};
+
+    /// A fresh in-memory database with every migration applied
+    async fn test_db() -> AppDatabase {
+        let db = AppDatabase::new("sqlite::memory:").await.unwrap();
+        db.initialize().await.unwrap();
+        db
+    }
+
+    async fn user(db: &AppDatabase, email: &str) -> User {
+        let user = User::new("Sam".into(), email.into());
+        db.create_user(&user, "hash").await.unwrap();
+        user
+    }
+
+    async fn business(db: &AppDatabase, name: &str) -> Business {
+        let business = Business::new(name.into(), "Food".into(), String::new(), String::new(), String::new(), None);
+        db.create_business(&business, None).await.unwrap();
+        business
+    }
+
+    #[tokio::test]
+    async fn held_reviews_are_never_stored_visible() {
+        let db = test_db().await;
+        let sam = user(&db, "sam@example.com").await;
+        let cafe = business(&db, "Cafe").await;
+        let held = Screening { comment: "Call 555-0100 for cheap watches".into(), flags: vec![ContentFlag::PhoneNumber], needs_moderation: true };
+
+        let review = Review::new(cafe.id.clone(), sam.id.clone(), 5, held.comment.clone());
+        let saved = db.create_review(&review, Some(&held)).await.unwrap();
+        assert!(saved.is_hidden);
+        assert_eq!(db.get_business_by_id(&cafe.id).await.unwrap().unwrap().review_count, 0);
+        assert!(db.search_businesses("watches").await.unwrap().results.is_empty());
+        let queue = db.get_moderation_queue().await.unwrap();
+        assert_eq!(queue[0].flags, [ContentFlag::PhoneNumber]);
+
+        // Same for an edit of a visible review
+        let other = business(&db, "Diner").await;
+        let clean = Review::new(other.id.clone(), sam.id.clone(), 4, "Great pancakes".into());
+        db.create_review(&clean, None).await.unwrap();
+        let edited = db.update_review(&clean.id, &sam.id, 4, &held.comment, Some(&held)).await.unwrap();
+        assert!(edited.is_hidden);
+        assert_eq!(db.get_business_by_id(&other.id).await.unwrap().unwrap().review_count, 0);
+    }
+
+    #[tokio::test]
+    async fn deleting_a_review_does_not_reset_rate_limits() {
+        let db = test_db().await;
+        let sam = user(&db, "sam@example.com").await;
+        let cafe = business(&db, "Cafe").await;
+        let limits = ReviewLimits { max_per_hour: 1, cooldown_seconds: 60 };
+
+        let review = Review::new(cafe.id.clone(), sam.id.clone(), 4, "Lovely little spot".into());
+        db.create_review(&review, None).await.unwrap();
+        db.delete_review(&review.id).await.unwrap();
+
+        let now = Utc::now();
+        let activity = db.get_review_activity(&sam.id, now).await.unwrap();
+        assert_eq!(activity.created_last_hour, 1);
+        assert!(matches!(
+            check_review_limits(&limits, &activity, true, now),
+            Err(RateLimitError::Cooldown { .. })
+        ));
+        // Still capped once the cooldown has passed
+        assert!(matches!(
+            check_review_limits(&limits, &activity, true, now + Duration::minutes(5)),
+            Err(RateLimitError::HourlyLimit { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn unused_codes_dont_use_up_a_deal() {
+        let db = test_db().await;
+        let (sam, kim) = (user(&db, "sam@example.com").await, user(&db, "kim@example.com").await);
+        let cafe = business(&db, "Cafe").await;
+        let now = Utc::now();
+        let deal = Deal {
+            max_redemptions: Some(1),
+            ..Deal::new(cafe.id.clone(), "Free coffee".into(), "One cup".into(), None, now, now + Duration::days(1))
+        };
+        db.create_deal(&deal).await.unwrap();
+
+        // Sam never uses their code, which doesn't stop Kim
+        let sams = db.issue_deal_code(&deal, &sam.id).await.unwrap();
+        let kims = db.issue_deal_code(&deal, &kim.id).await.unwrap();
+        db.redeem_deal(&deal, &kim.id, &kims.code).await.unwrap();
+
+        assert_eq!(
+            db.redeem_deal(&deal, &sam.id, &sams.code).await.unwrap_err(),
+            AppError::Conflict("This deal has been fully redeemed".to_string())
+        );
+    }
+
+    /// Businesses with known ratings, review counts and deal flags for `query_businesses`
+    async fn seeded() -> AppDatabase {
+        let db = test_db().await;
+        let seed = [
+            ("Joe's Pizza", "Food", "Wood-fired pizza", 4.5, 40, true),
+            ("Pizza Palace", "Food", "Slices by the pound", 3.0, 10, false),
+            ("Bean There Coffee", "Food", "Espresso and pastries", 4.8, 5, false),
+            ("Quick Clean", "Services", "Dry cleaning", 4.0, 25, true),
+        ];
+        for (name, category, description, average_rating, review_count, has_deals) in seed {
+            let business = Business {
+                average_rating,
+                review_count,
+                has_deals,
+                ..Business::new(name.into(), category.into(), description.into(), String::new(), String::new(), None)
+            };
+            db.create_business(&business, None).await.unwrap();
+        }
+        db
+    }
+
+    async fn names(db: &AppDatabase, query: BusinessQuery) -> (u64, Vec<String>) {
+        let page = db.query_businesses(&query, 0.0).await.unwrap();
+        (page.total, page.businesses.into_iter().map(|b| b.name).collect())
+    }
+
+    #[tokio::test]
+    async fn filters_combine() {
+        let db = seeded().await;
+
+        let query = BusinessQuery { category: Some("Food".into()), min_rating: Some(4.0), ..Default::default() };
+        assert_eq!(names(&db, query).await, (2, vec!["Bean There Coffee".into(), "Joe's Pizza".into()]));
+
+        let query = BusinessQuery { has_deals: Some(true), min_review_count: Some(30), ..Default::default() };
+        assert_eq!(names(&db, query).await, (1, vec!["Joe's Pizza".into()]));
+    }
+
+    #[tokio::test]
+    async fn sorts_by_key_and_direction() {
+        let db = seeded().await;
+
+        let query = BusinessQuery { sort: BusinessSort::ReviewCount, ..Default::default() };
+        assert_eq!(names(&db, query).await.1, ["Joe's Pizza", "Quick Clean", "Pizza Palace", "Bean There Coffee"]);
+
+        let query = BusinessQuery { sort: BusinessSort::Name, direction: SortDirection::Asc, ..Default::default() };
+        assert_eq!(names(&db, query).await.1, ["Bean There Coffee", "Joe's Pizza", "Pizza Palace", "Quick Clean"]);
+    }
+
+    #[tokio::test]
+    async fn rating_ties_break_on_review_count() {
+        let db = test_db().await;
+        for (name, review_count) in [("A", 3), ("B", 12), ("C", 7), ("D", 1), ("E", 20), ("F", 9)] {
+            let business = Business {
+                average_rating: 4.0,
+                review_count,
+                ..Business::new(name.into(), "Food".into(), String::new(), String::new(), String::new(), None)
+            };
+            db.create_business(&business, None).await.unwrap();
+        }
+
+        let query = BusinessQuery { sort: BusinessSort::Rating, ..Default::default() };
+        assert_eq!(names(&db, query).await.1, ["E", "B", "F", "C", "A", "D"]);
+    }
+
+    #[tokio::test]
+    async fn pages_keep_the_full_total() {
+        let db = seeded().await;
+        let page = |offset| BusinessQuery {
+            sort: BusinessSort::Name,
+            direction: SortDirection::Asc,
+            offset,
+            limit: Some(3),
+            ..Default::default()
         };
 
-        let question = format!("{} {} {} = ?", num1, operation, num2);
-        (question, result.to_string())
+        assert_eq!(names(&db, page(0)).await, (4, vec!["Bean There Coffee".into(), "Joe's Pizza".into(), "Pizza Palace".into()]));
+        assert_eq!(names(&db, page(3)).await, (4, vec!["Quick Clean".into()]));
+        assert_eq!(names(&db, page(4)).await, (4, vec![]));
+    }
+
+    #[tokio::test]
+    async fn text_search_filters_and_ranks() {
+        let db = seeded().await;
+
+        let query = BusinessQuery { search: Some("pizza".into()), sort: BusinessSort::Relevance, ..Default::default() };
+        let (total, found) = names(&db, query).await;
+        assert_eq!(total, 2);
+        assert!(found.iter().all(|name| name.contains("Pizza")));
+
+        let query = BusinessQuery { search: Some("  ".into()), ..Default::default() };
+        assert_eq!(names(&db, query).await.0, 4);
+    }
+
+    #[tokio::test]
+    async fn unsearchable_text_matches_nothing() {
+        let db = seeded().await;
+
+        let query = BusinessQuery { search: Some("!!!".into()), ..Default::default() };
+        assert_eq!(names(&db, query).await, (0, vec![]));
+    }
+
+    #[tokio::test]
+    async fn hidden_reviews_leave_the_index() {
+        let db = seeded().await;
+        let business = db.get_all_businesses().await.unwrap().into_iter().find(|b| b.name == "Quick Clean").unwrap();
+        let sam = user(&db, "sam@example.com").await;
+        let review = Review::new(business.id, sam.id, 1, "Lost my favourite scarf".into());
+        db.create_review(&review, None).await.unwrap();
+        let search = || BusinessQuery { search: Some("scarf".into()), ..Default::default() };
+
+        assert_eq!(names(&db, search()).await.0, 1);
+        db.moderate_review(&review.id, true).await.unwrap();
+        assert_eq!(names(&db, search()).await.0, 0);
+        db.moderate_review(&review.id, false).await.unwrap();
+        assert_eq!(names(&db, search()).await.0, 1);
     }
 }
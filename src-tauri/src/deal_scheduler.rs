@@ -0,0 +1,55 @@
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+
+use crate::database::AppDatabase;
+use crate::error::AppResult;
+use crate::models::DealStateChange;
+
+/// Event emitted with a `DealStateChange` payload whenever a deal starts or ends
+pub const DEAL_STATE_EVENT: &str = "deal-state-changed";
+
+/// Longest the scheduler sleeps, so deals created in the meantime are picked up
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// Keep `deals.is_active` and `businesses.has_deals` in step with deal dates
+/// for as long as the app runs
+pub fn start(app: AppHandle, db: Arc<Mutex<AppDatabase>>) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let sleep = match tick(&app, &db).await {
+                Ok(sleep) => sleep,
+                Err(e) => {
+                    eprintln!("Deal scheduler failed: {}", e);
+                    MAX_SLEEP
+                }
+            };
+            tokio::time::sleep(sleep).await;
+        }
+    });
+}
+
+/// Apply due transitions, announce them, and work out how long until the next one
+async fn tick(app: &AppHandle, db: &Mutex<AppDatabase>) -> AppResult<Duration> {
+    let now = Utc::now();
+    let db = db.lock().await;
+
+    for change in db.sync_deal_states(now).await? {
+        announce(app, &change);
+    }
+
+    let next = db.next_deal_transition(now).await?;
+    Ok(next
+        .and_then(|at| (at - now).to_std().ok())
+        .map_or(MAX_SLEEP, |wait| wait.min(MAX_SLEEP)))
+}
+
+/// Tell the frontend a deal started or ended, whether the scheduler or an
+/// owner's edit did it
+pub fn announce(app: &AppHandle, change: &DealStateChange) {
+    if let Err(e) = app.emit(DEAL_STATE_EVENT, change) {
+        eprintln!("Failed to emit {} for deal {}: {}", DEAL_STATE_EVENT, change.deal_id, e);
+    }
+}
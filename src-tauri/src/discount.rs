@@ -0,0 +1,202 @@
+use crate::models::{Deal, DealTerms, DiscountLine, DiscountQuote};
+
+/// Apply a deal's terms to a cart. Stacking rules:
+/// - every minimum spend must be met, or nothing applies
+/// - percentages come off first (each on what's left), then fixed amounts,
+///   then free items and buy-X-get-Y
+/// - the discount never exceeds the cart total
+///
+/// Works in whole cents so the lines always add up to the discount.
+pub fn calculate_discount(deal: &Deal, cart_total: f64) -> DiscountQuote {
+    let cart = to_cents(cart_total);
+    let minimum = deal
+        .terms
+        .iter()
+        .filter_map(|t| match t {
+            DealTerms::MinimumSpend { amount } => Some(to_cents(*amount)),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0);
+
+    let mut quote = DiscountQuote {
+        deal_id: deal.id.clone(),
+        cart_total: to_dollars(cart),
+        discount: 0.0,
+        final_total: to_dollars(cart),
+        lines: Vec::new(),
+        shortfall: None,
+    };
+    if cart < minimum {
+        quote.shortfall = Some(to_dollars(minimum - cart));
+        return quote;
+    }
+
+    let mut ordered: Vec<&DealTerms> = deal.terms.iter().collect();
+    ordered.sort_by_key(|t| stage(t));
+
+    let mut remaining = cart;
+    for terms in ordered {
+        let amount = match terms {
+            DealTerms::PercentOff { percent, max_discount } => {
+                let off = (remaining as f64 * percent / 100.0).round() as i64;
+                max_discount.map_or(off, |cap| off.min(to_cents(cap)))
+            }
+            DealTerms::AmountOff { amount } => to_cents(*amount),
+            DealTerms::FreeItem { value, .. } => to_cents(*value),
+            // The cart is taken to be made up of items at `item_price`
+            DealTerms::BuyXGetY { buy, get, item_price } => {
+                let price = to_cents(*item_price);
+                let group = (*buy + *get) as i64;
+                if price <= 0 || group == 0 {
+                    0
+                } else {
+                    (cart / price) / group * *get as i64 * price
+                }
+            }
+            DealTerms::MinimumSpend { .. } => continue,
+        }
+        .clamp(0, remaining);
+
+        if amount > 0 {
+            remaining -= amount;
+            quote.lines.push(DiscountLine { terms: terms.clone(), amount: to_dollars(amount) });
+        }
+    }
+
+    quote.discount = to_dollars(cart - remaining);
+    quote.final_total = to_dollars(remaining);
+    quote
+}
+
+/// What's wrong with a deal's terms, if anything
+pub fn terms_problem(terms: &[DealTerms]) -> Option<&'static str> {
+    let positive = |amount: f64| amount.is_finite() && amount > 0.0;
+    terms.iter().find_map(|t| match t {
+        DealTerms::PercentOff { percent, .. } if !(percent.is_finite() && *percent > 0.0 && *percent <= 100.0) => {
+            Some("percent off must be between 0 and 100")
+        }
+        DealTerms::PercentOff { max_discount: Some(cap), .. } if !positive(*cap) => {
+            Some("discount cap must be more than zero")
+        }
+        DealTerms::AmountOff { amount } | DealTerms::MinimumSpend { amount } if !positive(*amount) => {
+            Some("amounts must be more than zero")
+        }
+        DealTerms::BuyXGetY { buy, get, item_price } if *buy == 0 || *get == 0 || !positive(*item_price) => {
+            Some("buy-X-get-Y needs at least one item each way and a price")
+        }
+        DealTerms::FreeItem { item, value } if item.trim().is_empty() || !positive(*value) => {
+            Some("free item needs a name and a value")
+        }
+        _ => None,
+    })
+}
+
+/// Order in which terms come off the cart
+fn stage(terms: &DealTerms) -> u8 {
+    match terms {
+        DealTerms::MinimumSpend { .. } => 0,
+        DealTerms::PercentOff { .. } => 1,
+        DealTerms::AmountOff { .. } => 2,
+        DealTerms::FreeItem { .. } | DealTerms::BuyXGetY { .. } => 3,
+    }
+}
+
+fn to_cents(dollars: f64) -> i64 {
+    if dollars.is_finite() {
+        (dollars * 100.0).round() as i64
+    } else {
+        0
+    }
+}
+
+fn to_dollars(cents: i64) -> f64 {
+    cents as f64 / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn deal(terms: Vec<DealTerms>) -> Deal {
+        let now = Utc::now();
+        Deal { terms, ..Deal::new("b".into(), "Sale".into(), "d".into(), None, now, now + Duration::days(1)) }
+    }
+
+    fn percent(percent: f64, max_discount: Option<f64>) -> DealTerms {
+        DealTerms::PercentOff { percent, max_discount }
+    }
+
+    #[test]
+    fn percent_off_respects_its_cap() {
+        let quote = calculate_discount(&deal(vec![percent(20.0, Some(15.0))]), 50.0);
+        assert_eq!((quote.discount, quote.final_total), (10.0, 40.0));
+
+        let quote = calculate_discount(&deal(vec![percent(20.0, Some(15.0))]), 200.0);
+        assert_eq!((quote.discount, quote.final_total), (15.0, 185.0));
+    }
+
+    #[test]
+    fn minimum_spend_is_a_threshold() {
+        let terms = vec![DealTerms::MinimumSpend { amount: 30.0 }, DealTerms::AmountOff { amount: 5.0 }];
+
+        let short = calculate_discount(&deal(terms.clone()), 29.99);
+        assert_eq!((short.discount, short.shortfall), (0.0, Some(0.01)));
+        assert!(short.lines.is_empty());
+
+        let exact = calculate_discount(&deal(terms), 30.0);
+        assert_eq!((exact.discount, exact.shortfall), (5.0, None));
+    }
+
+    #[test]
+    fn percentages_stack_before_fixed_amounts() {
+        // Listed amount-first, still applied percent-first: 100 - 10% = 90, then - 10
+        let quote = calculate_discount(&deal(vec![DealTerms::AmountOff { amount: 10.0 }, percent(10.0, None)]), 100.0);
+        assert_eq!(quote.final_total, 80.0);
+        assert_eq!(quote.lines.iter().map(|l| l.amount).collect::<Vec<_>>(), [10.0, 10.0]);
+        assert!(matches!(quote.lines[0].terms, DealTerms::PercentOff { .. }));
+
+        // Two percentages compound rather than add up
+        let quote = calculate_discount(&deal(vec![percent(50.0, None), percent(50.0, None)]), 100.0);
+        assert_eq!(quote.final_total, 25.0);
+    }
+
+    #[test]
+    fn never_discounts_below_zero() {
+        let terms = vec![DealTerms::AmountOff { amount: 25.0 }, DealTerms::FreeItem { item: "Fries".into(), value: 4.0 }];
+        let quote = calculate_discount(&deal(terms), 20.0);
+
+        assert_eq!((quote.discount, quote.final_total), (20.0, 0.0));
+        assert_eq!(quote.lines.len(), 1);
+        assert_eq!(calculate_discount(&deal(vec![percent(10.0, None)]), 0.0).lines, []);
+    }
+
+    #[test]
+    fn buy_x_get_y_counts_whole_groups() {
+        let bogo = || deal(vec![DealTerms::BuyXGetY { buy: 2, get: 1, item_price: 4.0 }]);
+
+        // 5 items: one full group of 3, so one free
+        assert_eq!(calculate_discount(&bogo(), 20.0).discount, 4.0);
+        // 6 items: two groups
+        assert_eq!(calculate_discount(&bogo(), 24.0).discount, 8.0);
+        assert_eq!(calculate_discount(&bogo(), 8.0).discount, 0.0);
+    }
+
+    #[test]
+    fn rounds_to_the_cent() {
+        let quote = calculate_discount(&deal(vec![percent(15.0, None)]), 9.99);
+        assert_eq!((quote.discount, quote.final_total), (1.5, 8.49));
+    }
+
+    #[test]
+    fn rejects_nonsense_terms() {
+        assert_eq!(terms_problem(&[percent(20.0, None), DealTerms::MinimumSpend { amount: 10.0 }]), None);
+        assert!(terms_problem(&[percent(0.0, None)]).is_some());
+        assert!(terms_problem(&[percent(120.0, None)]).is_some());
+        assert!(terms_problem(&[percent(10.0, Some(-1.0))]).is_some());
+        assert!(terms_problem(&[DealTerms::AmountOff { amount: f64::NAN }]).is_some());
+        assert!(terms_problem(&[DealTerms::BuyXGetY { buy: 1, get: 0, item_price: 3.0 }]).is_some());
+        assert!(terms_problem(&[DealTerms::FreeItem { item: " ".into(), value: 2.0 }]).is_some());
+    }
+}
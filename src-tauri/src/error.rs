@@ -0,0 +1,199 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use sqlx::error::ErrorKind;
+use std::fmt;
+
+use crate::rate_limit::RateLimitError;
+
+/// A single failed field check
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub reason: String,
+}
+
+/// Result type used by the database and command layers
+pub type AppResult<T> = Result<T, AppError>;
+
+/// Error returned to the frontend. Serializes as
+/// `{ "code": "NOT_FOUND", "message": "...", "details": ... }` where `code` is
+/// stable and safe to branch on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    /// The named record doesn't exist
+    NotFound(String),
+    /// The write clashes with existing data (e.g. a duplicate email)
+    Conflict(String),
+    /// One or more fields failed validation
+    Validation(Vec<FieldError>),
+    /// No one is signed in, or the credentials were wrong
+    Unauthorized(String),
+    /// The acting user isn't allowed to do this
+    Forbidden(String),
+    /// Too many requests; details say when to retry
+    RateLimited(RateLimitError),
+    /// Anything unexpected (I/O, SQL syntax, ...)
+    Internal(String),
+}
+
+impl AppError {
+    /// Stable machine-readable error code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) => "NOT_FOUND",
+            Self::Conflict(_) => "CONFLICT",
+            Self::Validation(_) => "VALIDATION",
+            Self::Unauthorized(_) => "UNAUTHORIZED",
+            Self::Forbidden(_) => "FORBIDDEN",
+            Self::RateLimited(_) => "RATE_LIMITED",
+            Self::Internal(_) => "INTERNAL",
+        }
+    }
+
+    /// Validation error for a single field
+    pub fn validation(field: &str, reason: impl Into<String>) -> Self {
+        Self::Validation(vec![FieldError {
+            field: field.to_string(),
+            reason: reason.into(),
+        }])
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(what) => write!(f, "{} not found", what),
+            Self::Conflict(message)
+            | Self::Unauthorized(message)
+            | Self::Forbidden(message)
+            | Self::Internal(message) => f.write_str(message),
+            Self::Validation(errors) => {
+                let parts: Vec<String> = errors.iter().map(|e| format!("{} {}", e.field, e.reason)).collect();
+                f.write_str(&parts.join("; "))
+            }
+            Self::RateLimited(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("AppError", 3)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("message", &self.to_string())?;
+        match self {
+            Self::Validation(errors) => s.serialize_field("details", &serde_json::json!({ "fields": errors }))?,
+            Self::RateLimited(e) => s.serialize_field("details", e)?,
+            _ => s.serialize_field("details", &None::<()>)?,
+        }
+        s.end()
+    }
+}
+
+impl From<RateLimitError> for AppError {
+    fn from(e: RateLimitError) -> Self {
+        Self::RateLimited(e)
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::RowNotFound => Self::NotFound("Record".to_string()),
+            sqlx::Error::Database(db) => {
+                from_constraint(db.kind(), db.message()).unwrap_or_else(|| Self::Internal(e.to_string()))
+            }
+            _ => Self::Internal(e.to_string()),
+        }
+    }
+}
+
+impl From<sqlx::migrate::MigrateError> for AppError {
+    fn from(e: sqlx::migrate::MigrateError) -> Self {
+        Self::Internal(format!("Failed to run migrations: {}", e))
+    }
+}
+
+/// Adds context to database errors the way `anyhow::Context` does, while
+/// keeping constraint violations as typed errors
+pub trait DbContext<T> {
+    fn context(self, message: &str) -> AppResult<T>;
+}
+
+impl<T> DbContext<T> for Result<T, sqlx::Error> {
+    fn context(self, message: &str) -> AppResult<T> {
+        self.map_err(|e| match AppError::from(e) {
+            AppError::Internal(detail) => AppError::Internal(format!("{}: {}", message, detail)),
+            typed => typed,
+        })
+    }
+}
+
+/// Map a SQLite constraint failure onto a typed error using its message,
+/// e.g. `UNIQUE constraint failed: users.email`
+fn from_constraint(kind: ErrorKind, message: &str) -> Option<AppError> {
+    let target = message.split_once(": ").map(|(_, t)| t).unwrap_or("");
+    // "users.email" -> "email"; "favorites.user_id, favorites.business_id" -> "user_id"
+    let column = target
+        .split([',', ' ', '.'])
+        .filter(|t| !t.is_empty())
+        .nth(1)
+        .unwrap_or(target);
+
+    match kind {
+        ErrorKind::UniqueViolation => Some(AppError::Conflict(match target {
+            "users.email" => "Email already registered".to_string(),
+            "favorites.user_id, favorites.business_id" => "Business is already a favorite".to_string(),
+            "reviews.business_id, reviews.user_id" => "You have already reviewed this business".to_string(),
+            "review_responses.review_id" => "This review already has a reply".to_string(),
+            "review_reports.review_id, review_reports.user_id" => "You have already reported this review".to_string(),
+            _ => format!("{} already exists", target),
+        })),
+        ErrorKind::CheckViolation => {
+            // CHECK messages carry the expression, e.g. "rating >= 1 AND rating <= 5"
+            let field = target.split_whitespace().next().unwrap_or("value");
+            Some(AppError::validation(field, format!("must satisfy {}", target)))
+        }
+        ErrorKind::NotNullViolation => Some(AppError::validation(column, "is required")),
+        ErrorKind::ForeignKeyViolation => Some(AppError::NotFound("Referenced record".to_string())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_unique_violations_to_conflicts() {
+        assert_eq!(
+            from_constraint(ErrorKind::UniqueViolation, "UNIQUE constraint failed: users.email"),
+            Some(AppError::Conflict("Email already registered".to_string()))
+        );
+    }
+
+    #[test]
+    fn maps_check_and_not_null_to_validation() {
+        assert_eq!(
+            from_constraint(ErrorKind::CheckViolation, "CHECK constraint failed: rating >= 1 AND rating <= 5"),
+            Some(AppError::validation("rating", "must satisfy rating >= 1 AND rating <= 5"))
+        );
+        assert_eq!(
+            from_constraint(ErrorKind::NotNullViolation, "NOT NULL constraint failed: businesses.name"),
+            Some(AppError::validation("name", "is required"))
+        );
+    }
+
+    #[test]
+    fn serializes_with_stable_code() {
+        let json = serde_json::to_value(AppError::validation("rating", "must be between 1 and 5")).unwrap();
+        assert_eq!(json["code"], "VALIDATION");
+        assert_eq!(json["details"]["fields"][0]["field"], "rating");
+
+        let json = serde_json::to_value(AppError::NotFound("Business".to_string())).unwrap();
+        assert_eq!(json["code"], "NOT_FOUND");
+        assert_eq!(json["message"], "Business not found");
+    }
+}
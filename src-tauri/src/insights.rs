@@ -0,0 +1,277 @@
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use crate::models::{ReviewInsights, ReviewKeyword};
+use crate::sentiment;
+
+/// Keywords returned on each side (pros and cons)
+const MAX_KEYWORDS: usize = 5;
+
+/// A term has to show up in at least this many reviews to count as recurring
+const MIN_MENTIONS: u32 = 2;
+
+/// Terms whose mean sentiment is this close to zero are neither a pro nor a con
+const NEUTRAL_BAND: f64 = 0.05;
+
+/// Longest quote returned, in characters
+const MAX_QUOTE_LEN: usize = 160;
+
+/// Words that say nothing about the business on their own
+const STOPWORDS: &[&str] = &[
+    "a", "about", "after", "again", "all", "also", "always", "am", "an", "and", "any", "are", "as", "at",
+    "back", "be", "because", "been", "before", "being", "both", "but", "by", "came", "can", "cant", "come",
+    "could", "couldnt", "definitely", "did", "didnt", "do", "does", "doesnt", "dont", "down", "during", "each",
+    "even", "ever", "every", "few", "for", "from", "get", "go", "going", "got", "had", "has", "have", "he",
+    "her", "here", "him", "his", "how", "i", "if", "im", "in", "into", "is", "isnt", "it", "its", "ive",
+    "just", "me", "more", "most", "much", "my", "never", "no", "not", "nothing", "of", "off", "on", "once",
+    "one", "only", "or", "other", "our", "out", "over", "own", "pretty", "quite", "really", "same", "she",
+    "should", "so", "some", "still", "such", "super", "than", "that", "thats", "the", "their", "them", "then",
+    "there", "these", "they", "this", "those", "through", "to", "too", "under", "until", "up", "us", "very",
+    "was", "wasnt", "we", "went", "were", "werent", "what", "when", "where", "which", "while", "who", "why",
+    "will", "with", "wont", "would", "wouldnt", "you", "your", "absolutely", "extremely", "highly",
+    "incredibly", "totally", "truly", "fairly", "kinda", "slightly", "somewhat",
+];
+
+/// A visible review's comment and the business it belongs to
+pub struct ReviewText {
+    pub business_id: String,
+    pub comment: String,
+}
+
+/// A clause of a review, scored on its own so mixed reviews split into pros and cons
+struct Passage<'a> {
+    text: &'a str,
+    terms: HashSet<String>,
+    sentiment: f64,
+}
+
+/// Running totals for one term across a business's reviews
+#[derive(Default)]
+struct TermStats<'a> {
+    mentions: u32,
+    passages: u32,
+    sentiment_sum: f64,
+    most_positive: Option<(&'a str, f64)>,
+    most_negative: Option<(&'a str, f64)>,
+}
+
+/// Pull the recurring keywords and phrases out of a business's reviews,
+/// weighted by TF-IDF against every review in `corpus`
+pub fn summarize(business_id: &str, corpus: &[ReviewText]) -> ReviewInsights {
+    let mut document_frequency: HashMap<String, u32> = HashMap::new();
+    let mut stats: HashMap<String, TermStats> = HashMap::new();
+    let mut review_count = 0;
+
+    for review in corpus {
+        let passages = split_passages(&review.comment);
+        let terms: HashSet<&String> = passages.iter().flat_map(|p| &p.terms).collect();
+        for &term in &terms {
+            *document_frequency.entry(term.clone()).or_default() += 1;
+        }
+        if review.business_id != business_id {
+            continue;
+        }
+
+        review_count += 1;
+        for &term in &terms {
+            stats.entry(term.clone()).or_default().mentions += 1;
+        }
+        for passage in &passages {
+            for term in &passage.terms {
+                let entry = stats.entry(term.clone()).or_default();
+                entry.passages += 1;
+                entry.sentiment_sum += passage.sentiment;
+                if entry.most_positive.is_none_or(|(_, s)| passage.sentiment > s) {
+                    entry.most_positive = Some((passage.text, passage.sentiment));
+                }
+                if entry.most_negative.is_none_or(|(_, s)| passage.sentiment < s) {
+                    entry.most_negative = Some((passage.text, passage.sentiment));
+                }
+            }
+        }
+    }
+
+    // Smoothed IDF: words every business gets ("place", "staff") weigh less
+    // than ones that set this business apart
+    let total = corpus.len() as f64;
+    let mut candidates: Vec<(String, f64, &TermStats)> = stats
+        .iter()
+        .filter(|(_, s)| s.mentions >= MIN_MENTIONS)
+        .map(|(term, s)| {
+            let df = document_frequency.get(term).copied().unwrap_or(0) as f64;
+            let idf = ((1.0 + total) / (1.0 + df)).ln() + 1.0;
+            (term.clone(), s.mentions as f64 * idf, s)
+        })
+        .collect();
+    // On equal weight prefer the longer phrase, so "iced coffee" beats "coffee"
+    candidates.sort_by(|a, b| {
+        b.1.total_cmp(&a.1)
+            .then_with(|| word_count(&b.0).cmp(&word_count(&a.0)))
+            .then_with(|| a.0.cmp(&b.0))
+    });
+
+    let mut pros: Vec<ReviewKeyword> = Vec::new();
+    let mut cons: Vec<ReviewKeyword> = Vec::new();
+    for (term, score, s) in candidates {
+        let sentiment = s.sentiment_sum / s.passages as f64;
+        let (side, quote) = if sentiment >= NEUTRAL_BAND {
+            (&mut pros, s.most_positive)
+        } else if sentiment <= -NEUTRAL_BAND {
+            (&mut cons, s.most_negative)
+        } else {
+            continue;
+        };
+        if side.len() >= MAX_KEYWORDS || side.iter().any(|k| overlaps(&k.term, &term)) {
+            continue;
+        }
+
+        side.push(ReviewKeyword {
+            term,
+            score: score as f32,
+            mentions: s.mentions,
+            sentiment: sentiment as f32,
+            quote: quote.map(|(text, _)| truncate_quote(text)).unwrap_or_default(),
+        });
+    }
+
+    ReviewInsights {
+        business_id: business_id.to_string(),
+        review_count,
+        pros,
+        cons,
+        generated_at: Utc::now(),
+    }
+}
+
+/// Split a comment into sentences, and sentences into clauses around "but"
+fn split_passages(comment: &str) -> Vec<Passage<'_>> {
+    comment
+        .split(['.', '!', '?', ';', '\n'])
+        .flat_map(|sentence| sentence.split(" but "))
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(|text| Passage { text, terms: extract_terms(text), sentiment: sentiment::score(text) })
+        .collect()
+}
+
+/// Candidate keywords in a passage: topic words, plus two-word phrases such
+/// as "friendly staff" that may carry sentiment
+fn extract_terms(text: &str) -> HashSet<String> {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(sentiment::normalize)
+        .filter(|w| !w.is_empty())
+        .collect();
+    let is_content = |w: &str| !STOPWORDS.contains(&w) && !w.chars().all(|c| c.is_ascii_digit());
+
+    let mut terms = HashSet::new();
+    for word in &words {
+        if word.chars().count() >= 3 && is_content(word) && sentiment::valence(word).is_none() {
+            terms.insert(word.clone());
+        }
+    }
+    for pair in words.windows(2) {
+        if is_content(&pair[0]) && is_content(&pair[1]) && pair[0] != pair[1] {
+            terms.insert(format!("{} {}", pair[0], pair[1]));
+        }
+    }
+    terms
+}
+
+fn word_count(term: &str) -> usize {
+    term.split(' ').count()
+}
+
+/// Whether one term contains the other as whole words ("coffee" / "iced coffee")
+fn overlaps(a: &str, b: &str) -> bool {
+    let (a, b) = (format!(" {} ", a), format!(" {} ", b));
+    a.contains(&b) || b.contains(&a)
+}
+
+fn truncate_quote(text: &str) -> String {
+    if text.chars().count() <= MAX_QUOTE_LEN {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(MAX_QUOTE_LEN).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// Computed insights per business, kept until the reviews change
+#[derive(Default)]
+pub struct InsightCache {
+    entries: Mutex<HashMap<String, ReviewInsights>>,
+}
+
+impl InsightCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, business_id: &str) -> Option<ReviewInsights> {
+        self.entries.lock().unwrap().get(business_id).cloned()
+    }
+
+    pub fn insert(&self, insights: ReviewInsights) {
+        self.entries.lock().unwrap().insert(insights.business_id.clone(), insights);
+    }
+
+    /// Forget everything; any review changes the corpus-wide IDF weights
+    pub fn invalidate(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review(business_id: &str, comment: &str) -> ReviewText {
+        ReviewText { business_id: business_id.to_string(), comment: comment.to_string() }
+    }
+
+    #[test]
+    fn groups_keywords_into_pros_and_cons() {
+        let corpus = vec![
+            review("cafe", "The espresso is excellent. Parking is terrible."),
+            review("cafe", "Loved the espresso, the baristas are friendly. Parking was awful!"),
+            review("cafe", "Excellent espresso but parking is bad"),
+            review("pizzeria", "Great pizza and friendly staff"),
+        ];
+        let insights = summarize("cafe", &corpus);
+
+        assert_eq!(insights.review_count, 3);
+        let pro = insights.pros.iter().find(|k| k.term == "espresso").expect("espresso is a pro");
+        assert_eq!(pro.mentions, 3);
+        assert!(pro.quote.to_lowercase().contains("espresso"));
+        assert!(insights.cons.iter().any(|k| k.term == "parking"));
+        // Mentioned once, so not recurring
+        assert!(!insights.pros.iter().any(|k| k.term.contains("baristas")));
+        assert!(!insights.pros.iter().any(|k| k.term.contains("pizza")));
+    }
+
+    #[test]
+    fn prefers_phrases_over_their_words() {
+        let corpus = vec![
+            review("cafe", "The iced coffee was great"),
+            review("cafe", "Best iced coffee in town"),
+            review("cafe", "Their iced coffee is delicious"),
+        ];
+        let insights = summarize("cafe", &corpus);
+
+        let terms: Vec<&str> = insights.pros.iter().map(|k| k.term.as_str()).collect();
+        assert!(terms.contains(&"iced coffee"));
+        assert!(!terms.contains(&"coffee") && !terms.contains(&"iced"));
+    }
+
+    #[test]
+    fn common_words_rank_below_distinctive_ones() {
+        let mut corpus: Vec<ReviewText> = (0..5).map(|i| review(&format!("other{}", i), "Nice service")).collect();
+        corpus.push(review("cafe", "Nice service and lovely croissants"));
+        corpus.push(review("cafe", "Nice service, the croissants are lovely"));
+        let insights = summarize("cafe", &corpus);
+
+        let rank = |word: &str| insights.pros.iter().position(|k| k.term.contains(word));
+        assert!(rank("croissants").unwrap() < rank("service").unwrap());
+    }
+}
@@ -1,22 +1,129 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+mod auth;
+mod captcha;
 mod commands;
+mod config;
+mod content_filter;
+mod database;
+mod deal_scheduler;
+mod discount;
+mod error;
+mod insights;
+mod models;
+mod permissions;
+mod ranking;
+mod redemption;
+mod schedule;
+mod rate_limit;
+mod search;
+mod sentiment;
+mod validation;
 
+use auth::SessionStore;
+use captcha::{provider_from_config, CaptchaStore};
 use commands::*;
+use config::{AppConfig, CONFIG_FILE};
+use content_filter::ContentFilter;
+use database::AppDatabase;
+use error::AppError;
+use insights::InsightCache;
+use std::sync::Arc;
+use tauri::Manager;
+use tokio::sync::Mutex;
+
+/// File name of the SQLite database inside the app data directory
+const DATABASE_FILE: &str = "business_boost.db";
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .setup(|_app| {
-            // Simple setup without database initialization
-            println!("App starting up successfully");
+        .setup(|app| {
+            // Open (or create) the SQLite database in the platform app data directory
+            let data_dir = app.path().app_data_dir()?;
+            std::fs::create_dir_all(&data_dir)?;
+            let config = AppConfig::load(&data_dir.join(CONFIG_FILE))?;
+            let db_url = format!("sqlite://{}?mode=rwc", data_dir.join(DATABASE_FILE).display());
+
+            let db = tauri::async_runtime::block_on(async {
+                let db = AppDatabase::new(&db_url).await?;
+                db.initialize().await?;
+
+                let scored = db.score_unscored_reviews().await?;
+                if scored > 0 {
+                    println!("Scored sentiment for {} existing reviews", scored);
+                }
+
+                let repaired = db.recompute_all_ratings().await?;
+                if repaired > 0 {
+                    println!("Repaired cached ratings for {} businesses", repaired);
+                }
+                Ok::<_, AppError>(db)
+            })?;
+
+            let db = Arc::new(Mutex::new(db));
+            deal_scheduler::start(app.handle().clone(), db.clone());
+
+            app.manage(AppState {
+                db,
+                captcha: Arc::new(CaptchaStore::new(provider_from_config(&config.captcha))),
+                sessions: Arc::new(SessionStore::new()),
+                content_filter: Arc::new(ContentFilter::new(config.content_filter.clone())),
+                insights: Arc::new(InsightCache::new()),
+                config: Arc::new(config),
+            });
+
+            println!("Database ready at {}", data_dir.join(DATABASE_FILE).display());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
-            greet,
-            create_user,
+            initialize_app,
+            sign_up,
+            sign_in,
+            sign_out,
+            current_user,
             get_user,
+            set_user_role,
+            assign_business_owner,
+            create_business,
+            update_business,
             get_all_businesses,
+            get_business_by_id,
+            search_businesses,
+            query_businesses,
+            get_recommended_businesses,
+            create_review,
+            update_review,
+            delete_review,
+            get_review_history,
+            get_reviews_by_business,
+            get_review_insights,
+            vote_review,
+            remove_review_vote,
+            report_review,
+            get_moderation_queue,
+            hide_review,
+            restore_review,
+            respond_to_review,
+            update_review_response,
+            delete_review_response,
+            create_deal,
+            update_deal,
+            cancel_deal,
+            delete_deal,
+            get_deal_history,
+            claim_deal_code,
+            redeem_deal,
+            get_redemption_report,
+            calculate_discount,
+            get_deals_by_business,
+            get_active_deals,
+            add_favorite,
+            remove_favorite,
+            get_favorites_by_user,
+            is_favorite,
+            generate_captcha,
+            verify_captcha,
             generate_sample_data
         ])
         .run(tauri::generate_context!())
@@ -1,6 +1,6 @@
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveTime, Utc, Weekday};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Business {
@@ -13,11 +13,91 @@ pub struct Business {
     pub website: Option<String>,
     pub average_rating: f32,
     pub review_count: usize,
+    /// Mean sentiment (-1..1) of the visible reviews
+    pub average_sentiment: f32,
     pub has_deals: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Filters, sort order and page for `query_businesses`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct BusinessQuery {
+    pub category: Option<String>,
+    pub min_rating: Option<f32>,
+    pub min_review_count: Option<u32>,
+    pub has_deals: Option<bool>,
+    /// Free-text search, using the same syntax as `search_businesses`. Blank means
+    /// no text filter; text with no words in it (like "!!!") matches nothing.
+    pub search: Option<String>,
+    pub sort: BusinessSort,
+    pub direction: SortDirection,
+    pub offset: u32,
+    /// Page size; defaults to 20 and is capped at 100
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BusinessSort {
+    #[default]
+    Rating,
+    ReviewCount,
+    Name,
+    Newest,
+    /// Full-text relevance; falls back to rating when there is no search text
+    Relevance,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// One page of `query_businesses` results
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BusinessPage {
+    pub businesses: Vec<Business>,
+    /// Number of businesses matching the filters across all pages
+    pub total: u64,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+/// A business matched by full-text search
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchResult {
+    pub business: Business,
+    /// Relevance (negated BM25 for exact hits, 0..1 similarity for fuzzy ones), higher is better
+    pub score: f64,
+    /// Business name with matched terms wrapped in `<mark>` (HTML-escaped)
+    pub name_highlight: String,
+    /// Best-matching excerpt with matched terms wrapped in `<mark>` (HTML-escaped)
+    pub snippet: String,
+    pub match_kind: MatchKind,
+}
+
+/// How a search result matched the query
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    /// Matched the full-text index
+    Exact,
+    /// Close to the query but not an exact match (e.g. a typo)
+    Fuzzy,
+}
+
+/// Search results plus an optional "did you mean" correction
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchResponse {
+    pub results: Vec<SearchResult>,
+    pub did_you_mean: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Review {
     pub id: String,
@@ -25,10 +105,161 @@ pub struct Review {
     pub user_id: String,
     pub rating: u8,
     pub comment: String,
+    /// Cached number of "helpful" votes
+    pub helpful_count: u32,
+    /// Cached number of "unhelpful" votes
+    pub unhelpful_count: u32,
+    /// Hidden by moderation; left out of listings and the business rating
+    pub is_hidden: bool,
+    /// Lexicon sentiment of the comment, from -1 (negative) to 1 (positive)
+    pub sentiment: f32,
+    /// The comment's sentiment clearly contradicts the star rating
+    pub rating_mismatch: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Order for `get_reviews_by_business`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewSort {
+    #[default]
+    Newest,
+    Oldest,
+    /// Highest rating first
+    Highest,
+    /// Lowest rating first
+    Lowest,
+    /// Most net helpful votes first
+    MostHelpful,
+}
+
+/// Snapshot of a review taken just before it was edited
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewEdit {
+    pub id: String,
+    pub review_id: String,
+    pub previous_rating: u8,
+    pub previous_comment: String,
+    pub edited_at: DateTime<Utc>,
+}
+
+/// A user's helpful/unhelpful vote on someone else's review
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewVote {
+    pub id: String,
+    pub review_id: String,
+    pub user_id: String,
+    pub helpful: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Why a review was reported
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportReason {
+    Spam,
+    Offensive,
+    OffTopic,
+}
+
+impl ReportReason {
+    /// Value stored in `review_reports.reason`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Spam => "spam",
+            Self::Offensive => "offensive",
+            Self::OffTopic => "off_topic",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "offensive" => Self::Offensive,
+            "off_topic" => Self::OffTopic,
+            _ => Self::Spam,
+        }
+    }
+}
+
+/// A user's report of an abusive review
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewReport {
+    pub id: String,
+    pub review_id: String,
+    pub user_id: String,
+    pub reason: ReportReason,
+    /// Set once an admin has hidden or restored the review
+    pub resolved: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Something the content filter noticed in a review comment
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentFlag {
+    Profanity,
+    Link,
+    PhoneNumber,
+    RepeatedCharacters,
+    Shouting,
+    /// Nearly the same text as another review by the same user
+    Duplicate,
+}
+
+/// A review waiting for an admin, with its pending reports and any content filter flags
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModerationItem {
+    #[serde(flatten)]
+    pub review: Review,
+    pub reports: Vec<ReviewReport>,
+    pub flags: Vec<ContentFlag>,
+}
+
+/// A business owner's public reply to a review
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewResponse {
+    pub id: String,
+    pub review_id: String,
+    /// The owner who wrote the reply
+    pub user_id: String,
+    pub comment: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A review together with the owner's reply, if any
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewWithResponse {
+    #[serde(flatten)]
+    pub review: Review,
+    pub response: Option<ReviewResponse>,
+}
+
+/// A word or phrase that keeps coming up in a business's reviews
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewKeyword {
+    pub term: String,
+    /// TF-IDF weight; higher means more characteristic of this business
+    pub score: f32,
+    /// Number of reviews that mention it
+    pub mentions: u32,
+    /// Mean sentiment of the passages that mention it, from -1 to 1
+    pub sentiment: f32,
+    /// A passage from a review that mentions it
+    pub quote: String,
+}
+
+/// What reviewers like and dislike about a business
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewInsights {
+    pub business_id: String,
+    pub review_count: u32,
+    pub pros: Vec<ReviewKeyword>,
+    pub cons: Vec<ReviewKeyword>,
+    pub generated_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Deal {
     pub id: String,
@@ -39,10 +270,37 @@ pub struct Deal {
     pub start_date: DateTime<Utc>,
     pub end_date: DateTime<Utc>,
     pub is_active: bool,
+    /// Set when the owner ended the deal early
+    pub cancelled_at: Option<DateTime<Utc>>,
+    /// Total redemptions allowed across all users; None for unlimited
+    pub max_redemptions: Option<u32>,
+    pub max_redemptions_per_user: u32,
+    /// What the deal gives; see `discount::calculate_discount` for how they stack
+    pub terms: Vec<DealTerms>,
+    /// Weekly windows the deal is limited to, e.g. a happy hour; None runs all day
+    pub schedule: Option<DealSchedule>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// An owner's changes to a deal. Leaving out an optional field keeps the
+/// current value; the `clear_*` flags are how an edit removes one.
+#[derive(Debug, Clone)]
+pub struct DealEdit {
+    pub title: String,
+    pub description: String,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub discount_code: Option<String>,
+    pub max_redemptions: Option<u32>,
+    pub max_redemptions_per_user: Option<u32>,
+    pub terms: Option<Vec<DealTerms>>,
+    pub schedule: Option<DealSchedule>,
+    pub clear_discount_code: bool,
+    pub clear_max_redemptions: bool,
+    pub clear_schedule: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Favorite {
     pub id: String,
@@ -56,10 +314,59 @@ pub struct User {
     pub id: String,
     pub name: String,
     pub email: String,
+    pub role: UserRole,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// The part of an account anyone may see
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PublicProfile {
+    pub id: String,
+    pub name: String,
+}
+
+/// A looked-up account: in full for its owner or an admin, otherwise just the public profile
+#[derive(Debug, Serialize, Clone)]
+#[serde(untagged)]
+pub enum UserProfile {
+    Full(User),
+    Public(PublicProfile),
+}
+
+/// What a user may do; the rules live in `permissions`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UserRole {
+    /// Can review and favorite businesses
+    #[default]
+    Customer,
+    /// Can also manage the businesses and deals they own
+    Owner,
+    /// Can manage and moderate anything
+    Admin,
+}
+
+impl UserRole {
+    /// Value stored in `users.role`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Customer => "customer",
+            Self::Owner => "owner",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// Parse a stored role, treating anything unknown as a customer
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "owner" => Self::Owner,
+            "admin" => Self::Admin,
+            _ => Self::Customer,
+        }
+    }
+}
+
 impl Business {
     pub fn new(
         name: String,
@@ -80,6 +387,7 @@ impl Business {
             website,
             average_rating: 0.0,
             review_count: 0,
+            average_sentiment: 0.0,
             has_deals: false,
             created_at: now,
             updated_at: now,
@@ -101,6 +409,62 @@ impl Review {
             user_id,
             rating,
             comment,
+            helpful_count: 0,
+            unhelpful_count: 0,
+            is_hidden: false,
+            sentiment: 0.0,
+            rating_mismatch: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+impl ReviewEdit {
+    pub fn new(review: &Review) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            review_id: review.id.clone(),
+            previous_rating: review.rating,
+            previous_comment: review.comment.clone(),
+            edited_at: Utc::now(),
+        }
+    }
+}
+
+impl ReviewVote {
+    pub fn new(review_id: String, user_id: String, helpful: bool) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            review_id,
+            user_id,
+            helpful,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl ReviewReport {
+    pub fn new(review_id: String, user_id: String, reason: ReportReason) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            review_id,
+            user_id,
+            reason,
+            resolved: false,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl ReviewResponse {
+    pub fn new(review_id: String, user_id: String, comment: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            review_id,
+            user_id,
+            comment,
             created_at: now,
             updated_at: now,
         }
@@ -117,7 +481,7 @@ impl Deal {
         end_date: DateTime<Utc>,
     ) -> Self {
         let now = Utc::now();
-        Self {
+        let mut deal = Self {
             id: Uuid::new_v4().to_string(),
             business_id,
             title,
@@ -125,13 +489,225 @@ impl Deal {
             discount_code,
             start_date,
             end_date,
-            is_active: true,
+            is_active: false,
+            cancelled_at: None,
+            max_redemptions: None,
+            max_redemptions_per_user: 1,
+            terms: Vec::new(),
+            schedule: None,
             created_at: now,
             updated_at: now,
+        };
+        deal.is_active = deal.is_active_at(now);
+        deal
+    }
+
+    /// This deal with `edit` applied, as of `now`
+    pub fn edited(&self, edit: DealEdit, now: DateTime<Utc>) -> Self {
+        let mut deal = Self {
+            title: edit.title,
+            description: edit.description,
+            start_date: edit.start_date,
+            end_date: edit.end_date,
+            discount_code: keep_unless(edit.clear_discount_code, edit.discount_code, &self.discount_code),
+            max_redemptions: keep_unless(edit.clear_max_redemptions, edit.max_redemptions, &self.max_redemptions),
+            max_redemptions_per_user: edit.max_redemptions_per_user.unwrap_or(self.max_redemptions_per_user),
+            terms: edit.terms.unwrap_or_else(|| self.terms.clone()),
+            schedule: keep_unless(edit.clear_schedule, edit.schedule, &self.schedule),
+            updated_at: now,
+            ..self.clone()
+        };
+        deal.is_active = deal.is_active_at(now);
+        deal
+    }
+
+    /// Whether the deal is running at `now`; see `schedule::is_deal_active_at`
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        crate::schedule::is_deal_active_at(self, now)
+    }
+}
+
+fn keep_unless<T: Clone>(clear: bool, new: Option<T>, current: &Option<T>) -> Option<T> {
+    if clear { None } else { new.or_else(|| current.clone()) }
+}
+
+impl DealCode {
+    pub fn new(deal_id: String, user_id: String, code: String) -> Self {
+        Self {
+            code,
+            deal_id,
+            user_id,
+            issued_at: Utc::now(),
+            redeemed_at: None,
         }
     }
 }
 
+impl DealRedemption {
+    pub fn new(deal_id: String, user_id: String, code: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            deal_id,
+            user_id,
+            code,
+            redeemed_at: Utc::now(),
+        }
+    }
+}
+
+impl DealChange {
+    pub fn new(
+        deal: &Deal,
+        user_id: &str,
+        field: &str,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            deal_id: deal.id.clone(),
+            business_id: deal.business_id.clone(),
+            user_id: user_id.to_string(),
+            field: field.to_string(),
+            old_value,
+            new_value,
+            changed_at: Utc::now(),
+        }
+    }
+
+    /// One change per editable field that differs between `before` and `after`
+    pub fn between(before: &Deal, after: &Deal, user_id: &str) -> Vec<Self> {
+        let fields = [
+            ("title", Some(before.title.clone()), Some(after.title.clone())),
+            ("description", Some(before.description.clone()), Some(after.description.clone())),
+            ("discount_code", before.discount_code.clone(), after.discount_code.clone()),
+            ("start_date", Some(before.start_date.to_rfc3339()), Some(after.start_date.to_rfc3339())),
+            ("end_date", Some(before.end_date.to_rfc3339()), Some(after.end_date.to_rfc3339())),
+            ("max_redemptions", before.max_redemptions.map(|n| n.to_string()), after.max_redemptions.map(|n| n.to_string())),
+            (
+                "max_redemptions_per_user",
+                Some(before.max_redemptions_per_user.to_string()),
+                Some(after.max_redemptions_per_user.to_string()),
+            ),
+            ("terms", serde_json::to_string(&before.terms).ok(), serde_json::to_string(&after.terms).ok()),
+            (
+                "schedule",
+                before.schedule.as_ref().and_then(|s| serde_json::to_string(s).ok()),
+                after.schedule.as_ref().and_then(|s| serde_json::to_string(s).ok()),
+            ),
+        ];
+        fields
+            .into_iter()
+            .filter(|(_, old, new)| old != new)
+            .map(|(field, old, new)| Self::new(after, user_id, field, old, new))
+            .collect()
+    }
+}
+
+/// A weekly recurrence such as "weekdays 4-6pm". Times are on the local clock
+/// of `time_zone`; a window whose end is at or before its start runs past midnight.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DealSchedule {
+    pub weekdays: Vec<Weekday>,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    /// IANA name, e.g. "America/Chicago"
+    pub time_zone: String,
+}
+
+/// One rule of a deal. Amounts are in dollars.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DealTerms {
+    /// A percentage off, optionally capped at `max_discount`
+    PercentOff { percent: f64, max_discount: Option<f64> },
+    AmountOff { amount: f64 },
+    /// For every `buy` items at `item_price`, the next `get` are free
+    BuyXGetY { buy: u32, get: u32, item_price: f64 },
+    /// A named item worth `value` at no charge
+    FreeItem { item: String, value: f64 },
+    /// The other terms only apply once the cart reaches `amount`
+    MinimumSpend { amount: f64 },
+}
+
+/// One term's share of a discount
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DiscountLine {
+    pub terms: DealTerms,
+    pub amount: f64,
+}
+
+/// What a customer saves on a cart under a deal's terms
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DiscountQuote {
+    pub deal_id: String,
+    pub cart_total: f64,
+    pub discount: f64,
+    pub final_total: f64,
+    pub lines: Vec<DiscountLine>,
+    /// How much more the cart needs to reach the minimum spend, if it falls short
+    pub shortfall: Option<f64>,
+}
+
+/// One field of a deal changed by its owner
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DealChange {
+    pub id: String,
+    pub deal_id: String,
+    pub business_id: String,
+    /// Who made the change
+    pub user_id: String,
+    /// Column that changed, or "deal" when the whole deal was deleted
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// A single-use code issued to one user for a deal without a shared code
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DealCode {
+    pub code: String,
+    pub deal_id: String,
+    pub user_id: String,
+    pub issued_at: DateTime<Utc>,
+    pub redeemed_at: Option<DateTime<Utc>>,
+}
+
+/// A user redeeming a deal, with the code they used
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DealRedemption {
+    pub id: String,
+    pub deal_id: String,
+    pub user_id: String,
+    pub code: String,
+    pub redeemed_at: DateTime<Utc>,
+}
+
+/// Redemption counts for one deal, for its owner
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DealRedemptionReport {
+    pub deal_id: String,
+    pub title: String,
+    pub redemptions: u32,
+    /// Distinct users who redeemed it
+    pub unique_users: u32,
+    /// Single-use codes handed out, redeemed or not
+    pub codes_issued: u32,
+    pub max_redemptions: Option<u32>,
+    pub last_redeemed_at: Option<DateTime<Utc>>,
+}
+
+/// A deal that just started or ended, sent to the frontend as an event
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DealStateChange {
+    pub deal_id: String,
+    pub business_id: String,
+    pub is_active: bool,
+    /// Whether the business still has any active deal
+    pub business_has_deals: bool,
+}
+
 impl Favorite {
     pub fn new(user_id: String, business_id: String) -> Self {
         let now = Utc::now();
@@ -151,6 +727,7 @@ impl User {
             id: Uuid::new_v4().to_string(),
             name,
             email,
+            role: UserRole::default(),
             created_at: now,
             updated_at: now,
         }
@@ -0,0 +1,192 @@
+use std::collections::HashSet;
+
+use crate::error::{AppError, AppResult};
+use crate::models::UserRole;
+
+/// The signed-in user as seen by the permission checks
+#[derive(Debug, Clone)]
+pub struct Actor {
+    pub user_id: String,
+    pub role: UserRole,
+    /// Businesses this user is listed as an owner of
+    pub owned_businesses: HashSet<String>,
+}
+
+impl Actor {
+    pub fn owns(&self, business_id: &str) -> bool {
+        self.owned_businesses.contains(business_id)
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.role == UserRole::Admin
+    }
+}
+
+/// Something a command wants to do on the actor's behalf
+#[derive(Debug, Clone, Copy)]
+pub enum Action<'a> {
+    CreateBusiness,
+    EditBusiness { business_id: &'a str },
+    /// Create, change or remove deals of a business
+    ManageDeals { business_id: &'a str },
+    /// Claim a code for or redeem one of the business's deals
+    RedeemDeal { business_id: &'a str },
+    WriteReview { business_id: &'a str },
+    EditReview { author_id: &'a str },
+    DeleteReview { author_id: &'a str },
+    /// See earlier versions of a review, which may include hidden text
+    ViewReviewHistory { author_id: &'a str },
+    /// Mark someone else's review helpful or unhelpful
+    VoteOnReview { author_id: &'a str },
+    /// Flag someone else's review for moderation
+    ReportReview { author_id: &'a str },
+    /// Review the report queue, hide and restore reviews
+    ModerateReviews,
+    /// Post or edit the owner's reply to a review of this business
+    RespondToReview { business_id: &'a str },
+    DeleteResponse { business_id: &'a str },
+    Favorite,
+    /// Change roles and business ownership
+    ManageUsers,
+    /// See an account's email and role rather than just its name
+    ViewAccount { user_id: &'a str },
+}
+
+/// Decide whether `actor` may perform `action`
+pub fn authorize(actor: &Actor, action: Action) -> AppResult<()> {
+    let allowed = match action {
+        Action::CreateBusiness => matches!(actor.role, UserRole::Owner | UserRole::Admin),
+        Action::EditBusiness { business_id } | Action::ManageDeals { business_id } => {
+            actor.is_admin() || (actor.role == UserRole::Owner && actor.owns(business_id))
+        }
+        // Owners can review other businesses, but not their own
+        Action::WriteReview { business_id } | Action::RedeemDeal { business_id } => !actor.owns(business_id),
+        Action::EditReview { author_id } => actor.user_id == author_id,
+        Action::DeleteReview { author_id } | Action::ViewReviewHistory { author_id } => {
+            actor.user_id == author_id || actor.is_admin()
+        }
+        Action::VoteOnReview { author_id } => actor.user_id != author_id,
+        Action::ReportReview { author_id } => actor.user_id != author_id,
+        Action::ModerateReviews => actor.is_admin(),
+        Action::RespondToReview { business_id } => actor.role == UserRole::Owner && actor.owns(business_id),
+        Action::DeleteResponse { business_id } => {
+            actor.is_admin() || (actor.role == UserRole::Owner && actor.owns(business_id))
+        }
+        Action::Favorite => true,
+        Action::ManageUsers => actor.is_admin(),
+        Action::ViewAccount { user_id } => actor.user_id == user_id || actor.is_admin(),
+    };
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden(denial_message(action).to_string()))
+    }
+}
+
+fn denial_message(action: Action) -> &'static str {
+    match action {
+        Action::CreateBusiness => "Only business owners can add businesses",
+        Action::EditBusiness { .. } => "You can only edit businesses you own",
+        Action::ManageDeals { .. } => "You can only manage deals for businesses you own",
+        Action::WriteReview { .. } => "You can't review a business you own",
+        Action::RedeemDeal { .. } => "You can't redeem deals from a business you own",
+        Action::EditReview { .. } => "You can only edit your own reviews",
+        Action::DeleteReview { .. } => "You can only delete your own reviews",
+        Action::ViewReviewHistory { .. } => "You can only see the edit history of your own reviews",
+        Action::VoteOnReview { .. } => "You can't vote on your own review",
+        Action::ReportReview { .. } => "You can't report your own review",
+        Action::ModerateReviews => "Only administrators can moderate reviews",
+        Action::RespondToReview { .. } => "Only the business owner can reply to its reviews",
+        Action::DeleteResponse { .. } => "You can only delete replies to reviews of businesses you own",
+        Action::Favorite => "You can't favorite businesses",
+        Action::ManageUsers => "Only administrators can manage users",
+        Action::ViewAccount { .. } => "You can only see your own account details",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actor(role: UserRole, owns: &[&str]) -> Actor {
+        Actor {
+            user_id: "me".to_string(),
+            role,
+            owned_businesses: owns.iter().map(|b| b.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn customers_only_review_and_favorite() {
+        let customer = actor(UserRole::Customer, &[]);
+
+        assert!(authorize(&customer, Action::WriteReview { business_id: "b1" }).is_ok());
+        assert!(authorize(&customer, Action::Favorite).is_ok());
+        assert!(authorize(&customer, Action::CreateBusiness).is_err());
+        assert!(authorize(&customer, Action::EditBusiness { business_id: "b1" }).is_err());
+        assert!(authorize(&customer, Action::ManageDeals { business_id: "b1" }).is_err());
+        assert!(authorize(&customer, Action::ManageUsers).is_err());
+        assert!(authorize(&customer, Action::ViewAccount { user_id: "me" }).is_ok());
+        assert!(authorize(&customer, Action::ViewAccount { user_id: "someone" }).is_err());
+    }
+
+    #[test]
+    fn owners_manage_only_their_own_businesses() {
+        let owner = actor(UserRole::Owner, &["mine"]);
+
+        assert!(authorize(&owner, Action::CreateBusiness).is_ok());
+        assert!(authorize(&owner, Action::EditBusiness { business_id: "mine" }).is_ok());
+        assert!(authorize(&owner, Action::ManageDeals { business_id: "mine" }).is_ok());
+        assert_eq!(
+            authorize(&owner, Action::ManageDeals { business_id: "theirs" }),
+            Err(AppError::Forbidden("You can only manage deals for businesses you own".to_string()))
+        );
+        assert!(authorize(&owner, Action::WriteReview { business_id: "mine" }).is_err());
+        assert!(authorize(&owner, Action::WriteReview { business_id: "theirs" }).is_ok());
+        assert!(authorize(&owner, Action::RedeemDeal { business_id: "mine" }).is_err());
+        assert!(authorize(&owner, Action::RedeemDeal { business_id: "theirs" }).is_ok());
+    }
+
+    #[test]
+    fn only_the_business_owner_replies_to_reviews() {
+        let owner = actor(UserRole::Owner, &["mine"]);
+        let admin = actor(UserRole::Admin, &[]);
+        let customer = actor(UserRole::Customer, &[]);
+
+        assert!(authorize(&owner, Action::RespondToReview { business_id: "mine" }).is_ok());
+        assert!(authorize(&owner, Action::RespondToReview { business_id: "theirs" }).is_err());
+        assert!(authorize(&customer, Action::RespondToReview { business_id: "mine" }).is_err());
+        assert!(authorize(&admin, Action::RespondToReview { business_id: "mine" }).is_err());
+        assert!(authorize(&admin, Action::DeleteResponse { business_id: "mine" }).is_ok());
+    }
+
+    #[test]
+    fn admins_can_moderate_anything() {
+        let admin = actor(UserRole::Admin, &[]);
+
+        assert!(authorize(&admin, Action::EditBusiness { business_id: "any" }).is_ok());
+        assert!(authorize(&admin, Action::ManageDeals { business_id: "any" }).is_ok());
+        assert!(authorize(&admin, Action::DeleteReview { author_id: "someone" }).is_ok());
+        assert!(authorize(&admin, Action::ManageUsers).is_ok());
+        assert!(authorize(&admin, Action::ViewAccount { user_id: "someone" }).is_ok());
+        assert!(authorize(&admin, Action::ModerateReviews).is_ok());
+        assert!(authorize(&actor(UserRole::Owner, &["any"]), Action::ModerateReviews).is_err());
+        // Moderation doesn't extend to rewriting other people's words
+        assert!(authorize(&admin, Action::EditReview { author_id: "someone" }).is_err());
+    }
+
+    #[test]
+    fn authors_control_their_reviews() {
+        let customer = actor(UserRole::Customer, &[]);
+
+        assert!(authorize(&customer, Action::EditReview { author_id: "me" }).is_ok());
+        assert!(authorize(&customer, Action::DeleteReview { author_id: "me" }).is_ok());
+        assert!(authorize(&customer, Action::DeleteReview { author_id: "someone" }).is_err());
+        assert!(authorize(&customer, Action::ViewReviewHistory { author_id: "me" }).is_ok());
+        assert!(authorize(&customer, Action::ViewReviewHistory { author_id: "someone" }).is_err());
+        assert!(authorize(&actor(UserRole::Admin, &[]), Action::ViewReviewHistory { author_id: "someone" }).is_ok());
+        assert!(authorize(&customer, Action::VoteOnReview { author_id: "someone" }).is_ok());
+        assert!(authorize(&customer, Action::VoteOnReview { author_id: "me" }).is_err());
+    }
+}
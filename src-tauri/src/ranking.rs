@@ -0,0 +1,99 @@
+use std::cmp::Ordering;
+
+use crate::models::Business;
+
+/// Prior for the Bayesian average: every business is ranked as if it also had
+/// `strength` extra reviews at the site-wide mean rating, so a handful of
+/// reviews can't outrank a long track record
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RatingPrior {
+    pub mean: f64,
+    pub strength: f64,
+}
+
+impl RatingPrior {
+    /// Bayesian average of `count` ratings averaging `average`
+    pub fn weighted_rating(&self, average: f64, count: usize) -> f64 {
+        let count = count as f64;
+        if self.strength + count == 0.0 {
+            return 0.0;
+        }
+        (self.strength * self.mean + average * count) / (self.strength + count)
+    }
+}
+
+/// Sort best first by weighted rating, then by review count
+pub fn rank_businesses(businesses: &mut [Business], prior: RatingPrior) {
+    let score = |b: &Business| prior.weighted_rating(b.average_rating as f64, b.review_count);
+    businesses.sort_by(|a, b| {
+        score(b)
+            .partial_cmp(&score(a))
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| b.review_count.cmp(&a.review_count))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn business(name: &str, average_rating: f32, review_count: usize) -> Business {
+        Business {
+            average_rating,
+            review_count,
+            ..Business::new(name.to_string(), "Food".to_string(), String::new(), String::new(), String::new(), None)
+        }
+    }
+
+    fn ranked(mut businesses: Vec<Business>, prior: RatingPrior) -> Vec<String> {
+        rank_businesses(&mut businesses, prior);
+        businesses.into_iter().map(|b| b.name).collect()
+    }
+
+    const PRIOR: RatingPrior = RatingPrior { mean: 4.0, strength: 10.0 };
+
+    #[test]
+    fn long_track_record_beats_a_single_review() {
+        let businesses = vec![business("one-hit", 5.0, 1), business("established", 4.8, 200)];
+
+        assert_eq!(ranked(businesses, PRIOR), ["established", "one-hit"]);
+    }
+
+    #[test]
+    fn ranks_a_mixed_set_in_the_expected_order() {
+        let businesses = vec![
+            business("single five", 5.0, 1),
+            business("crowd favorite", 4.8, 200),
+            business("solid", 4.5, 40),
+            business("two bad", 3.0, 2),
+            business("rising", 4.9, 15),
+            business("consistently poor", 2.0, 50),
+        ];
+
+        assert_eq!(
+            ranked(businesses, PRIOR),
+            ["crowd favorite", "rising", "solid", "single five", "two bad", "consistently poor"]
+        );
+    }
+
+    #[test]
+    fn prior_strength_controls_the_shrinkage() {
+        let businesses = || vec![business("one-hit", 5.0, 1), business("established", 4.8, 200)];
+
+        // No prior is the plain mean
+        let none = RatingPrior { strength: 0.0, ..PRIOR };
+        assert_eq!(ranked(businesses(), none), ["one-hit", "established"]);
+
+        // A weak prior only pulls the single review part of the way down
+        assert!(RatingPrior { strength: 2.0, ..PRIOR }.weighted_rating(5.0, 1) > PRIOR.weighted_rating(5.0, 1));
+    }
+
+    #[test]
+    fn unreviewed_businesses_sit_at_the_prior_mean() {
+        assert_eq!(PRIOR.weighted_rating(0.0, 0), 4.0);
+        assert_eq!(RatingPrior { mean: 0.0, strength: 0.0 }.weighted_rating(0.0, 0), 0.0);
+
+        let businesses = vec![business("new", 0.0, 0), business("good", 4.6, 30), business("poor", 3.2, 30)];
+        assert_eq!(ranked(businesses, PRIOR), ["good", "new", "poor"]);
+    }
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::fmt;
+
+use crate::config::ReviewLimits;
+
+/// A user's recent review activity, as needed by the rate limiter
+#[derive(Debug, Clone, Default)]
+pub struct ReviewActivity {
+    /// New reviews written in the last hour, including deleted ones
+    pub created_last_hour: u32,
+    /// Oldest of those reviews, used to work out when a slot frees up
+    pub oldest_last_hour: Option<DateTime<Utc>>,
+    /// Most recent review submission or edit
+    pub last_submission: Option<DateTime<Utc>>,
+}
+
+/// Why a review submission was refused
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum RateLimitError {
+    /// Submitted again before the cooldown elapsed
+    Cooldown { retry_after_seconds: i64 },
+    /// Reached the cap on new reviews per hour
+    HourlyLimit { limit: u32, retry_after_seconds: i64 },
+}
+
+impl fmt::Display for RateLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cooldown { retry_after_seconds } => write!(
+                f,
+                "Please wait {} seconds before submitting another review",
+                retry_after_seconds
+            ),
+            Self::HourlyLimit { limit, retry_after_seconds } => write!(
+                f,
+                "You can post at most {} reviews per hour; try again in {} seconds",
+                limit, retry_after_seconds
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RateLimitError {}
+
+/// Check a submission against the configured limits; `is_new` is false when it
+/// replaces the user's existing review of the business
+pub fn check_review_limits(
+    limits: &ReviewLimits,
+    activity: &ReviewActivity,
+    is_new: bool,
+    now: DateTime<Utc>,
+) -> Result<(), RateLimitError> {
+    if let Some(last) = activity.last_submission {
+        let ready_at = last + Duration::seconds(limits.cooldown_seconds);
+        if ready_at > now {
+            return Err(RateLimitError::Cooldown {
+                retry_after_seconds: seconds_until(now, ready_at),
+            });
+        }
+    }
+
+    if is_new && activity.created_last_hour >= limits.max_per_hour {
+        let ready_at = activity.oldest_last_hour.unwrap_or(now) + Duration::hours(1);
+        return Err(RateLimitError::HourlyLimit {
+            limit: limits.max_per_hour,
+            retry_after_seconds: seconds_until(now, ready_at),
+        });
+    }
+
+    Ok(())
+}
+
+/// Whole seconds until `then`, rounded up so the UI never says "0 seconds"
+fn seconds_until(now: DateTime<Utc>, then: DateTime<Utc>) -> i64 {
+    let millis = (then - now).num_milliseconds().max(0);
+    ((millis + 999) / 1000).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> ReviewLimits {
+        ReviewLimits {
+            max_per_hour: 3,
+            cooldown_seconds: 60,
+        }
+    }
+
+    #[test]
+    fn first_review_is_allowed() {
+        let now = Utc::now();
+        assert_eq!(check_review_limits(&limits(), &ReviewActivity::default(), true, now), Ok(()));
+    }
+
+    #[test]
+    fn cooldown_applies_to_edits_too() {
+        let now = Utc::now();
+        let activity = ReviewActivity {
+            last_submission: Some(now - Duration::seconds(20)),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            check_review_limits(&limits(), &activity, false, now),
+            Err(RateLimitError::Cooldown { retry_after_seconds: 40 })
+        );
+    }
+
+    #[test]
+    fn hourly_cap_only_counts_new_reviews() {
+        let now = Utc::now();
+        let activity = ReviewActivity {
+            created_last_hour: 3,
+            oldest_last_hour: Some(now - Duration::minutes(45)),
+            last_submission: Some(now - Duration::minutes(5)),
+        };
+
+        assert_eq!(
+            check_review_limits(&limits(), &activity, true, now),
+            Err(RateLimitError::HourlyLimit { limit: 3, retry_after_seconds: 15 * 60 })
+        );
+        assert_eq!(check_review_limits(&limits(), &activity, false, now), Ok(()));
+    }
+}
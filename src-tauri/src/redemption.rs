@@ -0,0 +1,99 @@
+use rand::rngs::OsRng;
+use rand::Rng;
+
+use crate::error::{AppError, AppResult};
+use crate::models::Deal;
+
+/// Letters and digits that can't be mistaken for each other (no 0/O or 1/I/L)
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+
+/// Personal codes look like "K7QM-3XPD"
+const CODE_GROUPS: usize = 2;
+const CODE_GROUP_LEN: usize = 4;
+
+/// How many redemptions already count against a deal's limits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RedemptionCounts {
+    pub total: u32,
+    pub by_user: u32,
+}
+
+/// A random single-use code for one user
+pub fn generate_code() -> String {
+    let mut rng = OsRng;
+    (0..CODE_GROUPS)
+        .map(|_| {
+            (0..CODE_GROUP_LEN)
+                .map(|_| CODE_ALPHABET[rng.gen_range(0..CODE_ALPHABET.len())] as char)
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Codes are compared uppercase without surrounding whitespace
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+/// Refuse another redemption (or code) once either limit is used up
+pub fn check_limits(deal: &Deal, counts: RedemptionCounts) -> AppResult<()> {
+    if deal.max_redemptions.is_some_and(|max| counts.total >= max) {
+        return Err(AppError::Conflict("This deal has been fully redeemed".to_string()));
+    }
+    if counts.by_user >= deal.max_redemptions_per_user {
+        return Err(AppError::Conflict(match deal.max_redemptions_per_user {
+            1 => "You have already redeemed this deal".to_string(),
+            max => format!("You can redeem this deal at most {} times", max),
+        }));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn deal(max_redemptions: Option<u32>, max_redemptions_per_user: u32) -> Deal {
+        let now = Utc::now();
+        Deal {
+            max_redemptions,
+            max_redemptions_per_user,
+            ..Deal::new("b".into(), "Sale".into(), "Half off".into(), None, now, now + Duration::days(1))
+        }
+    }
+
+    #[test]
+    fn codes_are_readable_and_unique() {
+        let code = generate_code();
+        assert_eq!(code.len(), 9);
+        assert!(code.split('-').all(|group| group.bytes().all(|b| CODE_ALPHABET.contains(&b))));
+
+        let codes: std::collections::HashSet<String> = (0..1000).map(|_| generate_code()).collect();
+        assert_eq!(codes.len(), 1000);
+    }
+
+    #[test]
+    fn normalizes_typed_codes() {
+        assert_eq!(normalize_code("  k7qm-3xpd "), "K7QM-3XPD");
+    }
+
+    #[test]
+    fn enforces_overall_and_per_user_limits() {
+        let counts = |total, by_user| RedemptionCounts { total, by_user };
+
+        assert!(check_limits(&deal(None, 1), counts(500, 0)).is_ok());
+        assert!(check_limits(&deal(Some(10), 1), counts(9, 0)).is_ok());
+        assert_eq!(
+            check_limits(&deal(Some(10), 1), counts(10, 0)),
+            Err(AppError::Conflict("This deal has been fully redeemed".to_string()))
+        );
+        assert_eq!(
+            check_limits(&deal(None, 1), counts(3, 1)),
+            Err(AppError::Conflict("You have already redeemed this deal".to_string()))
+        );
+        assert!(check_limits(&deal(None, 3), counts(3, 2)).is_ok());
+        assert!(check_limits(&deal(None, 3), counts(3, 3)).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::models::{Deal, DealSchedule};
+
+/// Whether the deal is running at `instant`: not cancelled, inside its dates,
+/// and inside one of its weekly windows if it has a schedule
+pub fn is_deal_active_at(deal: &Deal, instant: DateTime<Utc>) -> bool {
+    deal.cancelled_at.is_none()
+        && deal.start_date <= instant
+        && instant < deal.end_date
+        && deal.schedule.as_ref().is_none_or(|s| is_open_at(s, instant))
+}
+
+/// Whether `instant` falls in one of the schedule's windows, read on the
+/// schedule's local clock. A window that ends at or before its start time runs
+/// past midnight and belongs to the day it started.
+pub fn is_open_at(schedule: &DealSchedule, instant: DateTime<Utc>) -> bool {
+    let local = instant.with_timezone(&time_zone(schedule)).naive_local();
+    let (day, time) = (local.weekday(), local.time());
+    let runs_on = |d| schedule.weekdays.contains(&d);
+
+    if schedule.start_time < schedule.end_time {
+        runs_on(day) && schedule.start_time <= time && time < schedule.end_time
+    } else {
+        (runs_on(day) && time >= schedule.start_time) || (runs_on(day.pred()) && time < schedule.end_time)
+    }
+}
+
+/// The next moment after `after` when the deal starts or stops running
+pub fn next_transition(deal: &Deal, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if deal.cancelled_at.is_some() {
+        return None;
+    }
+    let window_edges = deal.schedule.as_ref().map(|s| window_edges(s, after)).unwrap_or_default();
+    [deal.start_date, deal.end_date]
+        .into_iter()
+        .chain(window_edges.into_iter().filter(|at| deal.start_date < *at && *at < deal.end_date))
+        .filter(|at| *at > after)
+        .min()
+}
+
+/// What's wrong with a schedule, if anything
+pub fn schedule_problem(schedule: &DealSchedule) -> Option<&'static str> {
+    if schedule.weekdays.is_empty() {
+        Some("needs at least one day of the week")
+    } else if schedule.start_time == schedule.end_time {
+        Some("start and end times must differ")
+    } else if schedule.time_zone.parse::<Tz>().is_err() {
+        Some("time zone must be an IANA name like America/Chicago")
+    } else {
+        None
+    }
+}
+
+/// Window openings and closings from the day before `after` to a week past it.
+/// Local times skipped by a daylight-saving jump have no instant and are left
+/// out; the scheduler's regular wake-up covers them.
+fn window_edges(schedule: &DealSchedule, after: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    let tz = time_zone(schedule);
+    let today = after.with_timezone(&tz).date_naive();
+    let overnight = schedule.end_time <= schedule.start_time;
+
+    (-1..=7)
+        .map(|offset| today + Duration::days(offset))
+        .filter(|date| schedule.weekdays.contains(&date.weekday()))
+        .flat_map(|date| {
+            let end_date = if overnight { date + Duration::days(1) } else { date };
+            [
+                to_utc(&tz, date, schedule.start_time),
+                to_utc(&tz, end_date, schedule.end_time),
+            ]
+        })
+        .flatten()
+        .collect()
+}
+
+fn to_utc(tz: &Tz, date: NaiveDate, time: NaiveTime) -> Option<DateTime<Utc>> {
+    tz.from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+/// Validation rejects unknown zones, so UTC is only a fallback for old rows
+fn time_zone(schedule: &DealSchedule) -> Tz {
+    schedule.time_zone.parse().unwrap_or(Tz::UTC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().with_timezone(&Utc)
+    }
+
+    fn schedule(weekdays: Vec<Weekday>, start: &str, end: &str, time_zone: &str) -> DealSchedule {
+        DealSchedule {
+            weekdays,
+            start_time: start.parse().unwrap(),
+            end_time: end.parse().unwrap(),
+            time_zone: time_zone.to_string(),
+        }
+    }
+
+    fn deal(schedule: Option<DealSchedule>) -> Deal {
+        Deal {
+            schedule,
+            ..Deal::new(
+                "b".into(),
+                "Happy hour".into(),
+                "Half-price drinks".into(),
+                None,
+                at("2026-01-01T00:00:00Z"),
+                at("2027-01-01T00:00:00Z"),
+            )
+        }
+    }
+
+    fn happy_hour() -> DealSchedule {
+        // Tuesdays 3-5pm in Chicago
+        schedule(vec![Weekday::Tue], "15:00:00", "17:00:00", "America/Chicago")
+    }
+
+    #[test]
+    fn unscheduled_deals_run_for_their_whole_date_range() {
+        let deal = deal(None);
+        assert!(is_deal_active_at(&deal, at("2026-06-01T03:00:00Z")));
+        assert!(!is_deal_active_at(&deal, at("2025-12-31T23:59:59Z")));
+        assert!(!is_deal_active_at(&deal, at("2027-01-01T00:00:00Z")));
+    }
+
+    #[test]
+    fn windows_follow_local_time_across_daylight_saving() {
+        let deal = deal(Some(happy_hour()));
+
+        // October is CDT (UTC-5): 3pm local is 20:00 UTC
+        assert!(!is_deal_active_at(&deal, at("2026-10-13T19:59:00Z")));
+        assert!(is_deal_active_at(&deal, at("2026-10-13T20:00:00Z")));
+        assert!(!is_deal_active_at(&deal, at("2026-10-13T22:00:00Z")));
+        // January is CST (UTC-6): 3pm local is 21:00 UTC
+        assert!(!is_deal_active_at(&deal, at("2026-01-13T20:30:00Z")));
+        assert!(is_deal_active_at(&deal, at("2026-01-13T21:30:00Z")));
+        // Same time on a Wednesday
+        assert!(!is_deal_active_at(&deal, at("2026-10-14T20:30:00Z")));
+    }
+
+    #[test]
+    fn overnight_windows_belong_to_the_starting_day() {
+        // Friday 10pm to 2am Saturday, UTC
+        let late_night = deal(Some(schedule(vec![Weekday::Fri], "22:00:00", "02:00:00", "UTC")));
+
+        assert!(is_deal_active_at(&late_night, at("2026-10-16T23:00:00Z")));
+        assert!(is_deal_active_at(&late_night, at("2026-10-17T01:59:00Z")));
+        assert!(!is_deal_active_at(&late_night, at("2026-10-17T02:00:00Z")));
+        // Early Friday morning is the tail of Thursday, which isn't scheduled
+        assert!(!is_deal_active_at(&late_night, at("2026-10-16T01:00:00Z")));
+    }
+
+    #[test]
+    fn cancellation_and_dates_still_apply_to_scheduled_deals() {
+        let mut deal = deal(Some(happy_hour()));
+        deal.end_date = at("2026-10-13T21:00:00Z");
+        assert!(!is_deal_active_at(&deal, at("2026-10-13T21:30:00Z")));
+
+        deal.cancelled_at = Some(at("2026-10-01T00:00:00Z"));
+        assert!(!is_deal_active_at(&deal, at("2026-10-13T20:30:00Z")));
+        assert_eq!(next_transition(&deal, at("2026-10-13T20:30:00Z")), None);
+    }
+
+    #[test]
+    fn next_transition_finds_the_next_window_edge() {
+        let deal = deal(Some(happy_hour()));
+
+        // Saturday: next is Tuesday's opening
+        assert_eq!(next_transition(&deal, at("2026-10-17T12:00:00Z")), Some(at("2026-10-20T20:00:00Z")));
+        // During the window: its close
+        assert_eq!(next_transition(&deal, at("2026-10-13T20:30:00Z")), Some(at("2026-10-13T22:00:00Z")));
+        // Before the deal starts: the start date
+        assert_eq!(next_transition(&deal, at("2025-12-01T00:00:00Z")), Some(at("2026-01-01T00:00:00Z")));
+        // After the last window but before the end date: the end date
+        assert_eq!(next_transition(&deal, at("2026-12-30T00:00:00Z")), Some(at("2027-01-01T00:00:00Z")));
+    }
+
+    #[test]
+    fn rejects_unusable_schedules() {
+        assert_eq!(schedule_problem(&happy_hour()), None);
+        assert!(schedule_problem(&schedule(vec![], "15:00:00", "17:00:00", "UTC")).is_some());
+        assert!(schedule_problem(&schedule(vec![Weekday::Mon], "15:00:00", "15:00:00", "UTC")).is_some());
+        assert!(schedule_problem(&schedule(vec![Weekday::Mon], "15:00:00", "17:00:00", "Mars/Olympus")).is_some());
+    }
+}
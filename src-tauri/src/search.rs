@@ -0,0 +1,269 @@
+use crate::models::Business;
+
+/// Markers used by FTS5 `highlight()`/`snippet()`; replaced with `<mark>` after escaping
+pub const HIGHLIGHT_START: char = '\u{1}';
+pub const HIGHLIGHT_END: char = '\u{2}';
+
+/// Turn user input into a safe FTS5 MATCH expression.
+///
+/// `"quoted text"` becomes a phrase, `word*` an explicit prefix search, and the
+/// last bare word is also matched as a prefix so results update while typing.
+/// Everything else is reduced to plain terms, so FTS5 operators and LIKE
+/// wildcards typed by the user are never interpreted. Returns `None` if there is
+/// nothing left to search for.
+pub fn build_fts_query(input: &str) -> Option<String> {
+    let mut terms = Vec::new();
+
+    for (i, part) in input.split('"').enumerate() {
+        // Odd segments sit between a pair of quotes
+        if i % 2 == 1 {
+            let words = words(part);
+            if !words.is_empty() {
+                terms.push(format!("\"{}\"", words.join(" ")));
+            }
+            continue;
+        }
+
+        for raw in part.split_whitespace() {
+            let words = words(raw);
+            let prefix = raw.ends_with('*');
+            for (j, word) in words.iter().enumerate() {
+                if prefix && j == words.len() - 1 {
+                    terms.push(format!("\"{}\"*", word));
+                } else {
+                    terms.push(format!("\"{}\"", word));
+                }
+            }
+        }
+    }
+
+    // Treat a trailing bare word as a prefix so partial words still match
+    let ends_with_word = input.chars().last().is_some_and(|c| c.is_alphanumeric());
+    if ends_with_word && input.matches('"').count().is_multiple_of(2) {
+        if let Some(last) = terms.last_mut() {
+            if !last.ends_with('*') {
+                last.push('*');
+            }
+        }
+    }
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Split text into lowercase alphanumeric words
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// HTML-escape FTS5 output and turn the highlight markers into `<mark>` tags
+pub fn render_highlight(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            HIGHLIGHT_START => out.push_str("<mark>"),
+            HIGHLIGHT_END => out.push_str("</mark>"),
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Minimum word similarity (0..1) for a fuzzy match or a "did you mean" suggestion
+const FUZZY_THRESHOLD: f64 = 0.75;
+
+/// Query words shorter than this are too ambiguous to correct
+const MIN_FUZZY_WORD_LEN: usize = 3;
+
+/// Score businesses whose name or category nearly matches the query, best first.
+/// Businesses listed in `exclude` (usually the exact hits) are skipped.
+pub fn fuzzy_matches<'a>(query: &str, businesses: &'a [Business], exclude: &[&str]) -> Vec<(&'a Business, f64)> {
+    let query_words: Vec<String> = words(query)
+        .into_iter()
+        .filter(|w| w.chars().count() >= MIN_FUZZY_WORD_LEN)
+        .collect();
+    if query_words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<(&Business, f64)> = businesses
+        .iter()
+        .filter(|b| !exclude.contains(&b.id.as_str()))
+        .filter_map(|b| {
+            let candidates = words(&format!("{} {}", b.name, b.category));
+            let best: Vec<f64> = query_words
+                .iter()
+                .map(|q| candidates.iter().map(|c| similarity(q, c)).fold(0.0, f64::max))
+                .collect();
+
+            if best.iter().any(|s| *s >= FUZZY_THRESHOLD) {
+                Some((b, best.iter().sum::<f64>() / best.len() as f64))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    matches.sort_by(|a, b| b.1.total_cmp(&a.1));
+    matches
+}
+
+/// Suggest a corrected query using business names and categories as the
+/// vocabulary, or `None` if every word is already known or nothing is close
+pub fn suggest_correction(query: &str, businesses: &[Business]) -> Option<String> {
+    let mut vocabulary: Vec<String> = businesses
+        .iter()
+        .flat_map(|b| words(&format!("{} {}", b.name, b.category)))
+        .collect();
+    vocabulary.sort();
+    vocabulary.dedup();
+
+    let mut changed = false;
+    let corrected: Vec<String> = words(query)
+        .into_iter()
+        .map(|word| {
+            if word.chars().count() < MIN_FUZZY_WORD_LEN || vocabulary.binary_search(&word).is_ok() {
+                return word;
+            }
+            let best = vocabulary
+                .iter()
+                .map(|v| (v, similarity(&word, v)))
+                .filter(|(_, score)| *score >= FUZZY_THRESHOLD)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            match best {
+                Some((v, _)) => {
+                    changed = true;
+                    v.clone()
+                }
+                None => word,
+            }
+        })
+        .collect();
+
+    if changed {
+        Some(corrected.join(" "))
+    } else {
+        None
+    }
+}
+
+/// Similarity between two words: 1.0 for identical, falling with each edit
+fn similarity(a: &str, b: &str) -> f64 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - edit_distance(a, b) as f64 / longest as f64
+}
+
+/// Optimal string alignment distance: insertions, deletions, substitutions and
+/// transpositions of adjacent characters each cost one edit
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_word_is_a_prefix() {
+        assert_eq!(build_fts_query("pizza pl").as_deref(), Some("\"pizza\" \"pl\"*"));
+        assert_eq!(build_fts_query("pizza ").as_deref(), Some("\"pizza\""));
+    }
+
+    #[test]
+    fn phrases_and_explicit_prefixes() {
+        assert_eq!(
+            build_fts_query("\"auto repair\" tire*").as_deref(),
+            Some("\"auto repair\" \"tire\"*")
+        );
+        assert_eq!(build_fts_query("\"auto repair\"").as_deref(), Some("\"auto repair\""));
+    }
+
+    #[test]
+    fn operators_and_wildcards_are_neutralized() {
+        assert_eq!(build_fts_query("100% off_road NEAR(").as_deref(), Some("\"100\" \"off\" \"road\" \"near\""));
+        assert_eq!(build_fts_query("% _ \"\" *"), None);
+    }
+
+    #[test]
+    fn highlights_are_escaped() {
+        let text = format!("{}Joe's{} <b>", HIGHLIGHT_START, HIGHLIGHT_END);
+        assert_eq!(render_highlight(&text), "<mark>Joe&#39;s</mark> &lt;b&gt;");
+    }
+
+    fn business(name: &str, category: &str) -> Business {
+        Business::new(name.into(), category.into(), String::new(), String::new(), String::new(), None)
+    }
+
+    #[test]
+    fn edit_distance_counts_transpositions_once() {
+        assert_eq!(edit_distance("cofee", "coffee"), 1);
+        assert_eq!(edit_distance("piza", "pizza"), 1);
+        assert_eq!(edit_distance("retial", "retail"), 1);
+        assert_eq!(edit_distance("bakery", "bakery"), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn fuzzy_matches_typos_in_names_and_categories() {
+        let businesses = vec![
+            business("Bean There Coffee", "Food"),
+            business("Joe's Pizza", "Food"),
+            business("Quick Clean", "Services"),
+        ];
+
+        let hits = fuzzy_matches("cofee", &businesses, &[]);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.name, "Bean There Coffee");
+
+        let hits = fuzzy_matches("servces", &businesses, &[]);
+        assert_eq!(hits[0].0.name, "Quick Clean");
+
+        let excluded = businesses[1].id.clone();
+        assert!(fuzzy_matches("piza", &businesses, &[excluded.as_str()]).is_empty());
+    }
+
+    #[test]
+    fn suggests_closest_known_words() {
+        let businesses = vec![business("Joe's Pizza", "Food"), business("Bean There Coffee", "Food")];
+
+        assert_eq!(suggest_correction("cofee", &businesses).as_deref(), Some("coffee"));
+        assert_eq!(suggest_correction("piza joe", &businesses).as_deref(), Some("pizza joe"));
+        assert_eq!(suggest_correction("pizza", &businesses), None);
+        assert_eq!(suggest_correction("xylophone", &businesses), None);
+    }
+}
@@ -0,0 +1,187 @@
+/// VADER-style word valences from -4 (very negative) to +4 (very positive),
+/// tuned for local business reviews. Compiled in, so scoring works offline.
+const LEXICON: &[(&str, f64)] = &[
+    // Positive
+    ("affordable", 1.3), ("amazing", 2.8), ("attentive", 1.6), ("awesome", 3.1), ("beautiful", 2.9),
+    ("best", 3.2), ("better", 1.9), ("brilliant", 2.8), ("charming", 2.2), ("clean", 1.7),
+    ("comfortable", 1.8), ("cool", 1.3), ("courteous", 2.0), ("cozy", 1.8), ("decent", 0.9),
+    ("delicious", 2.7), ("delightful", 2.9), ("easy", 1.4), ("efficient", 1.5), ("enjoy", 2.2),
+    ("enjoyed", 2.3), ("excellent", 2.7), ("exceptional", 2.6), ("excited", 1.4), ("fabulous", 2.4),
+    ("fair", 1.3), ("fantastic", 2.6), ("fast", 1.0), ("favorite", 2.0), ("fine", 0.8),
+    ("fresh", 1.3), ("friendly", 2.2), ("fun", 2.3), ("gem", 2.0), ("generous", 2.3),
+    ("glad", 2.0), ("good", 1.9), ("gorgeous", 3.0), ("great", 3.1), ("happy", 2.7),
+    ("helpful", 1.8), ("honest", 2.3), ("impressed", 2.1), ("impressive", 2.3), ("incredible", 2.6),
+    ("kind", 2.4), ("knowledgeable", 1.6), ("love", 3.2), ("loved", 2.9), ("lovely", 2.8),
+    ("loves", 2.7), ("nice", 1.8), ("ok", 0.9), ("okay", 0.9), ("outstanding", 3.0),
+    ("perfect", 2.7), ("perfectly", 2.5), ("pleasant", 2.3), ("pleased", 1.9), ("polite", 1.5),
+    ("professional", 1.4), ("quick", 1.0), ("recommend", 1.5), ("recommended", 1.5), ("relaxing", 2.2),
+    ("reliable", 1.6), ("satisfied", 1.8), ("solid", 1.0), ("spotless", 2.0), ("superb", 3.1),
+    ("tasty", 2.1), ("terrific", 2.9), ("thank", 1.5), ("thanks", 1.9), ("welcoming", 1.8),
+    ("wonderful", 2.7), ("worth", 0.9), ("wow", 2.8), ("yummy", 2.4),
+    // Negative
+    ("angry", -2.3), ("annoying", -1.6), ("avoid", -1.6), ("awful", -2.0), ("bad", -2.5),
+    ("bland", -1.2), ("boring", -1.3), ("broken", -1.4), ("burnt", -1.3), ("careless", -1.5),
+    ("complaint", -1.5), ("crowded", -0.9), ("dangerous", -2.1), ("dirty", -1.9), ("disappointed", -1.9),
+    ("disappointing", -2.2), ("disappointment", -2.3), ("disgusting", -2.4), ("dreadful", -2.7), ("expensive", -0.8),
+    ("filthy", -2.6), ("frustrated", -1.5), ("frustrating", -1.9), ("greasy", -1.0), ("gross", -2.1),
+    ("hate", -2.7), ("hated", -3.0), ("horrible", -2.5), ("ignored", -1.5), ("incompetent", -2.3),
+    ("inedible", -2.4), ("issue", -0.9), ("issues", -0.9), ("lame", -1.8), ("lousy", -2.3),
+    ("meh", -0.6), ("mediocre", -1.0), ("mess", -1.5), ("messy", -1.4), ("nasty", -2.6),
+    ("noisy", -0.9), ("overcooked", -1.1), ("overpriced", -1.6), ("pathetic", -2.5), ("poor", -2.1),
+    ("poorly", -1.9), ("problem", -1.7), ("problems", -1.7), ("ripoff", -2.4), ("rotten", -2.3),
+    ("rude", -2.0), ("sad", -2.1), ("scam", -2.5), ("sick", -2.0), ("slow", -1.0),
+    ("smelly", -1.4), ("soggy", -1.2), ("stale", -1.4), ("sucked", -2.0), ("sucks", -1.5),
+    ("terrible", -2.1), ("unacceptable", -2.0), ("undercooked", -1.3), ("unfriendly", -1.5), ("unhelpful", -1.6),
+    ("unprofessional", -1.6), ("unsafe", -1.8), ("useless", -1.8), ("waste", -1.8), ("wasted", -2.2),
+    ("worse", -2.1), ("worst", -3.1), ("wrong", -2.1),
+];
+
+/// Words that flip the sentiment of what follows ("not good")
+const NEGATIONS: &[&str] = &[
+    "not", "no", "never", "none", "nobody", "nothing", "neither", "nor", "cannot", "without", "hardly",
+    "barely", "isnt", "arent", "wasnt", "werent", "dont", "doesnt", "didnt", "wont", "cant", "couldnt",
+    "shouldnt", "wouldnt",
+];
+
+/// Intensity modifiers ("very good", "slightly disappointing")
+const BOOSTERS: &[(&str, f64)] = &[
+    ("absolutely", 0.293), ("extremely", 0.293), ("highly", 0.293), ("incredibly", 0.293), ("really", 0.293),
+    ("so", 0.293), ("super", 0.293), ("totally", 0.293), ("truly", 0.293), ("very", 0.293),
+    ("fairly", -0.293), ("kinda", -0.293), ("slightly", -0.293), ("somewhat", -0.293),
+];
+
+/// How far back a negation or booster reaches
+const LOOKBACK: usize = 3;
+const NEGATION_SCALAR: f64 = -0.74;
+const CAPS_EMPHASIS: f64 = 0.733;
+const EXCLAMATION_EMPHASIS: f64 = 0.292;
+/// Normalization constant for mapping the raw sum into -1..1
+const ALPHA: f64 = 15.0;
+
+/// Compound score at or beyond which text clearly disagrees with a star rating
+const MISMATCH_THRESHOLD: f64 = 0.5;
+
+/// Compound sentiment of `text`, from -1 (most negative) to 1 (most positive)
+pub fn score(text: &str) -> f64 {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let words: Vec<String> = tokens.iter().map(|t| normalize(t)).collect();
+    // Caps only mean emphasis when the rest of the text isn't all caps
+    let mixed_case = text.chars().any(|c| c.is_lowercase());
+
+    let mut valences = vec![0.0; words.len()];
+    for (i, word) in words.iter().enumerate() {
+        let Some(mut valence) = lookup(LEXICON, word) else {
+            continue;
+        };
+        let sign = valence.signum();
+
+        if mixed_case && is_shouted(tokens[i]) {
+            valence += sign * CAPS_EMPHASIS;
+        }
+        for (distance, previous) in words[i.saturating_sub(LOOKBACK)..i].iter().rev().enumerate() {
+            if let Some(boost) = lookup(BOOSTERS, previous) {
+                valence += sign * boost * (1.0 - 0.05 * distance as f64);
+            }
+        }
+        let negated = (i.saturating_sub(LOOKBACK)..i).any(|j| is_negation(tokens[j], &words[j]));
+        if negated {
+            valence *= NEGATION_SCALAR;
+        }
+        valences[i] = valence;
+    }
+
+    // "but" shifts the weight onto the clause that follows it
+    if let Some(but) = words.iter().position(|w| w == "but") {
+        for (i, valence) in valences.iter_mut().enumerate() {
+            *valence *= if i < but { 0.5 } else { 1.5 };
+        }
+    }
+
+    let mut sum: f64 = valences.iter().sum();
+    if sum != 0.0 {
+        let exclamations = text.matches('!').count().min(4) as f64;
+        sum += sum.signum() * exclamations * EXCLAMATION_EMPHASIS;
+    }
+    sum / (sum * sum + ALPHA).sqrt()
+}
+
+/// Whether the text sentiment clearly contradicts the star rating, e.g. a
+/// 5-star review with very negative text
+pub fn is_rating_mismatch(rating: u8, score: f64) -> bool {
+    (rating >= 4 && score <= -MISMATCH_THRESHOLD) || (rating <= 2 && score >= MISMATCH_THRESHOLD)
+}
+
+/// Valence of a single normalized word, if it carries sentiment on its own
+pub fn valence(word: &str) -> Option<f64> {
+    lookup(LEXICON, word)
+}
+
+/// Lowercase letters only, with apostrophes dropped ("Isn't," -> "isnt")
+pub fn normalize(token: &str) -> String {
+    token.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+fn lookup(table: &[(&str, f64)], word: &str) -> Option<f64> {
+    table.iter().find(|(w, _)| *w == word).map(|(_, v)| *v)
+}
+
+fn is_negation(token: &str, word: &str) -> bool {
+    NEGATIONS.contains(&word) || token.to_lowercase().contains("n't") || token.to_lowercase().contains("n’t")
+}
+
+fn is_shouted(token: &str) -> bool {
+    let letters: Vec<char> = token.chars().filter(|c| c.is_alphabetic()).collect();
+    letters.len() > 1 && letters.iter().all(|c| c.is_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polarity() {
+        assert!(score("The staff were friendly and the food was delicious") > 0.5);
+        assert!(score("Rude staff and the food was disgusting") < -0.5);
+        assert_eq!(score("We went on a Tuesday"), 0.0);
+    }
+
+    #[test]
+    fn negation_flips_sentiment() {
+        assert!(score("The food was good") > 0.0);
+        assert!(score("The food was not good") < 0.0);
+        assert!(score("The food wasn't good at all") < 0.0);
+        assert!(score("Never disappointed here") > 0.0);
+    }
+
+    #[test]
+    fn intensity_cues() {
+        let plain = score("The service was good");
+        assert!(score("The service was very good") > plain);
+        assert!(score("The service was slightly good") < plain);
+        assert!(score("The service was GOOD") > plain);
+        assert!(score("The service was good!!!") > plain);
+    }
+
+    #[test]
+    fn but_favors_the_second_clause() {
+        assert!(score("The decor is nice but the food was terrible") < 0.0);
+        assert!(score("Parking is a problem but the food is amazing") > 0.0);
+    }
+
+    #[test]
+    fn stays_in_range() {
+        let gushing = "best best best amazing amazing perfect love love wonderful!!!!";
+        assert!(score(gushing) > 0.9 && score(gushing) <= 1.0);
+    }
+
+    #[test]
+    fn rating_mismatch() {
+        let negative = score("Terrible, rude staff and the worst food I have had");
+        assert!(is_rating_mismatch(5, negative));
+        assert!(!is_rating_mismatch(1, negative));
+
+        let positive = score("Absolutely wonderful, friendly and delicious");
+        assert!(is_rating_mismatch(1, positive));
+        assert!(!is_rating_mismatch(3, positive));
+    }
+}
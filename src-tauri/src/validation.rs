@@ -0,0 +1,426 @@
+use crate::discount::terms_problem;
+use crate::schedule::schedule_problem;
+use crate::error::{AppError, AppResult, FieldError};
+use crate::models::*;
+
+pub const MAX_NAME_LEN: usize = 100;
+pub const MAX_CATEGORY_LEN: usize = 50;
+pub const MAX_ADDRESS_LEN: usize = 200;
+pub const MAX_DESCRIPTION_LEN: usize = 2000;
+pub const MIN_COMMENT_LEN: usize = 10;
+pub const MAX_COMMENT_LEN: usize = 2000;
+pub const MAX_DEAL_TITLE_LEN: usize = 100;
+pub const MAX_EMAIL_LEN: usize = 254;
+
+/// Input checks run by the commands before anything touches the database
+pub trait Validate {
+    /// Trim text and canonicalize emails, phone numbers and URLs in place
+    fn normalize(&mut self) {}
+
+    /// Check every field, reporting all failures at once
+    fn validate(&self) -> AppResult<()>;
+
+    /// Normalize, then validate
+    fn validated(mut self) -> AppResult<Self>
+    where
+        Self: Sized,
+    {
+        self.normalize();
+        self.validate()?;
+        Ok(self)
+    }
+}
+
+/// Collects failing fields so they can be reported together
+#[derive(Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn check(&mut self, ok: bool, field: &str, reason: impl Into<String>) -> &mut Self {
+        if !ok {
+            self.errors.push(FieldError {
+                field: field.to_string(),
+                reason: reason.into(),
+            });
+        }
+        self
+    }
+
+    /// Non-blank text of at most `max` characters
+    pub fn text(&mut self, field: &str, value: &str, max: usize) -> &mut Self {
+        let len = value.trim().chars().count();
+        if len == 0 {
+            self.check(false, field, "is required")
+        } else {
+            self.check(len <= max, field, format!("must be at most {} characters", max))
+        }
+    }
+
+    pub fn rating(&mut self, rating: u8) -> &mut Self {
+        self.check((1..=5).contains(&rating), "rating", "must be between 1 and 5")
+    }
+
+    pub fn comment(&mut self, comment: &str) -> &mut Self {
+        let len = comment.trim().chars().count();
+        self.check(
+            (MIN_COMMENT_LEN..=MAX_COMMENT_LEN).contains(&len),
+            "comment",
+            format!("must be between {} and {} characters", MIN_COMMENT_LEN, MAX_COMMENT_LEN),
+        )
+    }
+
+    pub fn finish(&mut self) -> AppResult<()> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(std::mem::take(&mut self.errors)))
+        }
+    }
+}
+
+/// Trim and collapse internal runs of whitespace
+fn clean(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Lowercase and trim; doesn't check syntax
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Basic `local@domain.tld` syntax check
+pub fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    let labels: Vec<&str> = domain.split('.').collect();
+
+    email.len() <= MAX_EMAIL_LEN
+        && !local.is_empty()
+        && local.len() <= 64
+        && !local.starts_with('.')
+        && !local.ends_with('.')
+        && !local.contains("..")
+        && local.chars().all(|c| c.is_ascii_alphanumeric() || "._%+-'".contains(c))
+        && labels.len() >= 2
+        && labels.iter().all(|l| {
+            !l.is_empty()
+                && !l.starts_with('-')
+                && !l.ends_with('-')
+                && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        && labels.last().is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+/// Format a phone number as `555-0123`, `(555) 123-4567` or `+<digits>`;
+/// `None` if it isn't a plausible number
+pub fn normalize_phone(phone: &str) -> Option<String> {
+    let phone = phone.trim();
+    if !phone.chars().all(|c| c.is_ascii_digit() || " ()-.+".contains(c)) {
+        return None;
+    }
+    let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+
+    if phone.starts_with('+') && !phone.starts_with("+1") {
+        return (8..=15).contains(&digits.len()).then(|| format!("+{}", digits));
+    }
+
+    let digits = match digits.len() {
+        11 if digits.starts_with('1') => &digits[1..],
+        _ => digits.as_str(),
+    };
+    match digits.len() {
+        7 => Some(format!("{}-{}", &digits[..3], &digits[3..])),
+        10 => Some(format!("({}) {}-{}", &digits[..3], &digits[3..6], &digits[6..])),
+        _ => None,
+    }
+}
+
+/// Add `https://` if no scheme is given and lowercase the host; `None` if it
+/// isn't an http(s) URL with a dotted host name
+pub fn normalize_url(website: &str) -> Option<String> {
+    let website = website.trim();
+    let with_scheme = if website.contains("://") {
+        website.to_string()
+    } else {
+        format!("https://{}", website)
+    };
+
+    let url = url::Url::parse(&with_scheme).ok()?;
+    let host = url.host_str()?;
+    let valid = matches!(url.scheme(), "http" | "https") && host.contains('.') && !host.ends_with('.');
+    valid.then(|| url.to_string().trim_end_matches('/').to_string())
+}
+
+impl Validate for Business {
+    fn normalize(&mut self) {
+        self.name = clean(&self.name);
+        self.category = clean(&self.category);
+        self.description = self.description.trim().to_string();
+        self.address = clean(&self.address);
+        if let Some(phone) = normalize_phone(&self.phone) {
+            self.phone = phone;
+        }
+        self.website = match self.website.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(website) => Some(normalize_url(website).unwrap_or_else(|| website.to_string())),
+        };
+    }
+
+    fn validate(&self) -> AppResult<()> {
+        let mut v = Validator::new();
+        v.text("name", &self.name, MAX_NAME_LEN)
+            .text("category", &self.category, MAX_CATEGORY_LEN)
+            .text("description", &self.description, MAX_DESCRIPTION_LEN)
+            .text("address", &self.address, MAX_ADDRESS_LEN)
+            .check(normalize_phone(&self.phone).is_some(), "phone", "must be a valid phone number");
+        if let Some(website) = &self.website {
+            v.check(normalize_url(website).is_some(), "website", "must be a valid web address");
+        }
+        v.finish()
+    }
+}
+
+impl Validate for Review {
+    fn normalize(&mut self) {
+        self.comment = self.comment.trim().to_string();
+    }
+
+    fn validate(&self) -> AppResult<()> {
+        Validator::new()
+            .check(!self.business_id.is_empty(), "business_id", "is required")
+            .check(!self.user_id.is_empty(), "user_id", "is required")
+            .rating(self.rating)
+            .comment(&self.comment)
+            .finish()
+    }
+}
+
+impl Validate for ReviewResponse {
+    fn normalize(&mut self) {
+        self.comment = self.comment.trim().to_string();
+    }
+
+    fn validate(&self) -> AppResult<()> {
+        Validator::new()
+            .check(!self.review_id.is_empty(), "review_id", "is required")
+            .text("comment", &self.comment, MAX_COMMENT_LEN)
+            .finish()
+    }
+}
+
+impl Validate for Deal {
+    fn normalize(&mut self) {
+        self.title = clean(&self.title);
+        self.description = self.description.trim().to_string();
+        self.discount_code = match self.discount_code.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(code) => Some(code.to_uppercase()),
+        };
+    }
+
+    fn validate(&self) -> AppResult<()> {
+        let mut v = Validator::new();
+        v.check(!self.business_id.is_empty(), "business_id", "is required")
+            .text("title", &self.title, MAX_DEAL_TITLE_LEN)
+            .text("description", &self.description, MAX_DESCRIPTION_LEN)
+            .check(self.end_date > self.start_date, "end_date", "must be after the start date")
+            .check(self.max_redemptions_per_user >= 1, "max_redemptions_per_user", "must be at least 1");
+        if let Some(problem) = terms_problem(&self.terms) {
+            v.check(false, "terms", problem);
+        }
+        if let Some(problem) = self.schedule.as_ref().and_then(schedule_problem) {
+            v.check(false, "schedule", problem);
+        }
+        if let Some(max) = self.max_redemptions {
+            v.check(
+                max >= self.max_redemptions_per_user,
+                "max_redemptions",
+                "must be at least the per-user limit",
+            );
+        }
+        if let Some(code) = &self.discount_code {
+            v.check(
+                (3..=20).contains(&code.len()) && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'),
+                "discount_code",
+                "must be 3-20 letters, digits or dashes",
+            );
+        }
+        v.finish()
+    }
+}
+
+impl Validate for DealEdit {
+    fn validate(&self) -> AppResult<()> {
+        const BOTH: &str = "can't be set and cleared at once";
+        Validator::new()
+            .check(!(self.clear_discount_code && self.discount_code.is_some()), "discount_code", BOTH)
+            .check(!(self.clear_max_redemptions && self.max_redemptions.is_some()), "max_redemptions", BOTH)
+            .check(!(self.clear_schedule && self.schedule.is_some()), "schedule", BOTH)
+            .finish()
+    }
+}
+
+impl Validate for User {
+    fn normalize(&mut self) {
+        self.name = clean(&self.name);
+        self.email = normalize_email(&self.email);
+    }
+
+    fn validate(&self) -> AppResult<()> {
+        Validator::new()
+            .text("name", &self.name, MAX_NAME_LEN)
+            .check(is_valid_email(&self.email), "email", "must be a valid email address")
+            .finish()
+    }
+}
+
+impl Validate for Favorite {
+    fn validate(&self) -> AppResult<()> {
+        Validator::new()
+            .check(!self.user_id.is_empty(), "user_id", "is required")
+            .check(!self.business_id.is_empty(), "business_id", "is required")
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn failing_fields(result: AppResult<()>) -> Vec<String> {
+        match result {
+            Err(AppError::Validation(errors)) => errors.into_iter().map(|e| e.field).collect(),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn business_is_normalized() {
+        let business = Business::new(
+            "  Joe's   Pizza ".into(),
+            "Food".into(),
+            "Wood-fired pizza".into(),
+            "12 Main St".into(),
+            "1 (555) 123 4567".into(),
+            Some("JoesPizza.com/".into()),
+        )
+        .validated()
+        .unwrap();
+
+        assert_eq!(business.name, "Joe's Pizza");
+        assert_eq!(business.phone, "(555) 123-4567");
+        assert_eq!(business.website.as_deref(), Some("https://joespizza.com"));
+    }
+
+    #[test]
+    fn business_reports_every_bad_field() {
+        let business = Business::new(
+            "   ".into(),
+            "x".repeat(MAX_CATEGORY_LEN + 1),
+            "ok".into(),
+            "12 Main St".into(),
+            "call me".into(),
+            Some("not a url".into()),
+        );
+
+        assert_eq!(failing_fields(business.validated().map(|_| ())), ["name", "category", "phone", "website"]);
+    }
+
+    #[test]
+    fn review_checks_rating_and_comment() {
+        let review = Review::new("b".into(), "u".into(), 6, "  short ".into());
+        assert_eq!(failing_fields(review.validated().map(|_| ())), ["rating", "comment"]);
+    }
+
+    #[test]
+    fn deal_must_end_after_it_starts() {
+        let now = Utc::now();
+        let deal = Deal::new("b".into(), "Sale".into(), "Half off".into(), Some("ab".into()), now, now - Duration::days(1));
+        assert_eq!(failing_fields(deal.validated().map(|_| ())), ["end_date", "discount_code"]);
+    }
+
+    #[test]
+    fn deal_redemption_limits() {
+        let now = Utc::now();
+        let deal = Deal::new("b".into(), "Sale".into(), "Half off".into(), None, now, now + Duration::days(1));
+        let limits = |max_redemptions, max_redemptions_per_user| Deal {
+            max_redemptions,
+            max_redemptions_per_user,
+            ..deal.clone()
+        };
+
+        assert!(limits(None, 1).validated().is_ok());
+        assert!(limits(Some(100), 2).validated().is_ok());
+        assert_eq!(failing_fields(limits(Some(1), 2).validated().map(|_| ())), ["max_redemptions"]);
+        assert_eq!(failing_fields(limits(None, 0).validated().map(|_| ())), ["max_redemptions_per_user"]);
+    }
+
+    fn edit(deal: &Deal) -> DealEdit {
+        DealEdit {
+            title: "Bigger sale".into(),
+            description: deal.description.clone(),
+            start_date: deal.start_date,
+            end_date: deal.end_date,
+            discount_code: None,
+            max_redemptions: None,
+            max_redemptions_per_user: None,
+            terms: None,
+            schedule: None,
+            clear_discount_code: false,
+            clear_max_redemptions: false,
+            clear_schedule: false,
+        }
+    }
+
+    #[test]
+    fn deal_edits_keep_what_they_leave_out() {
+        let now = Utc::now();
+        let deal = Deal {
+            max_redemptions: Some(50),
+            ..Deal::new("b".into(), "Sale".into(), "Half off".into(), Some("SAVE10".into()), now, now + Duration::days(1))
+        };
+
+        let edited = deal.edited(edit(&deal), now);
+        assert_eq!(edited.title, "Bigger sale");
+        assert_eq!(edited.discount_code.as_deref(), Some("SAVE10"));
+        assert_eq!(edited.max_redemptions, Some(50));
+
+        let cleared = deal.edited(DealEdit { clear_discount_code: true, ..edit(&deal) }, now);
+        assert_eq!(cleared.discount_code, None);
+        assert_eq!(cleared.max_redemptions, Some(50));
+    }
+
+    #[test]
+    fn deal_edits_cant_set_and_clear_a_field() {
+        let now = Utc::now();
+        let deal = Deal::new("b".into(), "Sale".into(), "Half off".into(), None, now, now + Duration::days(1));
+        let edit = DealEdit {
+            discount_code: Some("SAVE20".into()),
+            clear_discount_code: true,
+            max_redemptions: Some(10),
+            ..edit(&deal)
+        };
+        assert_eq!(failing_fields(edit.validate()), ["discount_code"]);
+    }
+
+    #[test]
+    fn emails_and_phones() {
+        assert!(is_valid_email("first.last+tag@example.co.uk"));
+        assert!(!is_valid_email("no-at-sign.example.com"));
+        assert!(!is_valid_email("a@b"));
+        assert!(!is_valid_email("a..b@example.com"));
+        assert_eq!(User::new(" Sam ".into(), " Sam@Example.COM ".into()).validated().unwrap().email, "sam@example.com");
+
+        assert_eq!(normalize_phone("555.0123").as_deref(), Some("555-0123"));
+        assert_eq!(normalize_phone("+44 20 7946 0958").as_deref(), Some("+442079460958"));
+        assert_eq!(normalize_phone("12345"), None);
+    }
+}
@@ -10,19 +10,10 @@ function App() {
   const [error, setError] = useState(null);
 
   useEffect(() => {
-    // Initialize app with demo data (without database for now)
     const initializeApp = async () => {
       try {
-        // Create a demo user without database calls
-        const demoUser = {
-          id: "demo-user-123",
-          name: "FBLA User",
-          email: "fbla@example.com",
-          created_at: new Date().toISOString(),
-          updated_at: new Date().toISOString()
-        };
-
-        setUser(demoUser);
+        // Pick up the session if someone is already signed in
+        setUser(await invoke("current_user"));
         setLoading(false);
       } catch (err) {
         console.error("Failed to initialize app:", err);
@@ -56,7 +47,7 @@ function App() {
   return (
     <Router>
       <div className="app-container">
-        <Sidebar />
+        <Sidebar user={user} />
         <main className="main-content">
           <Routes>
             <Route path="/" element={<HomePage user={user} />} />
@@ -64,7 +55,7 @@ function App() {
             <Route path="/favorites" element={<FavoritesPage user={user} />} />
             <Route path="/deals" element={<DealsPage user={user} />} />
             <Route path="/business/:id" element={<BusinessDetailPage user={user} />} />
-            <Route path="/profile" element={<ProfilePage user={user} />} />
+            <Route path="/profile" element={<ProfilePage user={user} onUserChange={setUser} />} />
             <Route path="/settings" element={<SettingsPage user={user} />} />
             <Route path="/about" element={<AboutPage />} />
           </Routes>
@@ -75,7 +66,7 @@ function App() {
 }
 
 // Sidebar Navigation
-function Sidebar() {
+function Sidebar({ user }) {
   return (
     <nav className="sidebar">
       <div className="sidebar-header">
@@ -110,7 +101,7 @@ function Sidebar() {
         <li>
           <Link to="/profile" className="nav-link">
             <FaUser className="nav-icon" />
-            <span>Profile</span>
+            <span>{user ? "Profile" : "Sign In"}</span>
           </Link>
         </li>
       </ul>
@@ -409,10 +400,7 @@ function BusinessCard({ business, userId, showDetails = false }) {
     if (userId) {
       const checkFavorite = async () => {
         try {
-          const result = await invoke("is_favorite", {
-            userId,
-            businessId: business.id
-          });
+          const result = await invoke("is_favorite", { businessId: business.id });
           setIsFavorite(result);
         } catch (err) {
           console.error("Failed to check favorite status:", err);
@@ -429,16 +417,10 @@ function BusinessCard({ business, userId, showDetails = false }) {
     setFavoriteLoading(true);
     try {
       if (isFavorite) {
-        await invoke("remove_favorite", {
-          userId,
-          businessId: business.id
-        });
+        await invoke("remove_favorite", { businessId: business.id });
         setIsFavorite(false);
       } else {
-        await invoke("add_favorite", {
-          userId,
-          businessId: business.id
-        });
+        await invoke("add_favorite", { businessId: business.id });
         setIsFavorite(true);
       }
     } catch (err) {
@@ -543,7 +525,7 @@ function FavoritesPage({ user }) {
     const fetchFavorites = async () => {
       try {
         if (user?.id) {
-          const result = await invoke("get_favorites_by_user", { userId: user.id });
+          const result = await invoke("get_favorites_by_user");
           setFavorites(result);
         }
         setLoading(false);
@@ -563,7 +545,14 @@ function FavoritesPage({ user }) {
         <p>Businesses you've saved for quick access</p>
       </header>
 
-      {loading ? (
+      {!user ? (
+        <div className="empty-state">
+          <FaHeart className="empty-icon" />
+          <h3>Sign in to see your favorites</h3>
+          <p>Create an account to save businesses for quick access</p>
+          <Link to="/profile" className="cta-button">Sign In</Link>
+        </div>
+      ) : loading ? (
         <div className="loading-section">
           <div className="spinner"></div>
           <p>Loading your favorites...</p>
@@ -644,6 +633,28 @@ function DealsPage({ user }) {
 }
 
 // Business Detail Page
+// Find a nonce whose SHA-256 hash of "prefix:nonce" starts with `difficulty` zero bits
+async function solveProofOfWork(prefix, difficulty) {
+  const encoder = new TextEncoder();
+  for (let nonce = 0; ; nonce++) {
+    const digest = new Uint8Array(
+      await crypto.subtle.digest("SHA-256", encoder.encode(`${prefix}:${nonce}`))
+    );
+    let zeros = 0;
+    for (const byte of digest) {
+      if (byte === 0) {
+        zeros += 8;
+        continue;
+      }
+      zeros += Math.clz32(byte) - 24;
+      break;
+    }
+    if (zeros >= difficulty) {
+      return nonce.toString();
+    }
+  }
+}
+
 function BusinessDetailPage({ user }) {
   const [business, setBusiness] = useState(null);
   const [reviews, setReviews] = useState([]);
@@ -651,7 +662,7 @@ function BusinessDetailPage({ user }) {
   const [isFavorite, setIsFavorite] = useState(false);
   const [loading, setLoading] = useState(true);
   const [showReviewForm, setShowReviewForm] = useState(false);
-  const [captcha, setCaptcha] = useState({ question: "", answer: "" });
+  const [captcha, setCaptcha] = useState({ id: "", question: "" });
   const [userCaptchaAnswer, setUserCaptchaAnswer] = useState("");
 
   // Get business ID from URL
@@ -672,10 +683,7 @@ function BusinessDetailPage({ user }) {
           setBusiness(businessData);
 
           if (user?.id) {
-            const favoriteStatus = await invoke("is_favorite", {
-              userId: user.id,
-              businessId: businessData.id
-            });
+            const favoriteStatus = await invoke("is_favorite", { businessId: businessData.id });
             setIsFavorite(favoriteStatus);
           }
         }
@@ -697,16 +705,10 @@ function BusinessDetailPage({ user }) {
 
     try {
       if (isFavorite) {
-        await invoke("remove_favorite", {
-          userId: user.id,
-          businessId: business.id
-        });
+        await invoke("remove_favorite", { businessId: business.id });
         setIsFavorite(false);
       } else {
-        await invoke("add_favorite", {
-          userId: user.id,
-          businessId: business.id
-        });
+        await invoke("add_favorite", { businessId: business.id });
         setIsFavorite(true);
       }
     } catch (err) {
@@ -719,6 +721,9 @@ function BusinessDetailPage({ user }) {
       const newCaptcha = await invoke("generate_captcha");
       setCaptcha(newCaptcha);
       setUserCaptchaAnswer("");
+      if (newCaptcha.kind === "proof_of_work") {
+        setUserCaptchaAnswer(await solveProofOfWork(newCaptcha.prefix, newCaptcha.difficulty));
+      }
     } catch (err) {
       console.error("Failed to generate CAPTCHA:", err);
       alert("Failed to generate CAPTCHA. Please try again.");
@@ -731,13 +736,6 @@ function BusinessDetailPage({ user }) {
     const rating = parseInt(form.rating.value);
     const comment = form.comment.value;
 
-    // Validate CAPTCHA
-    if (userCaptchaAnswer !== captcha.answer) {
-      alert("Incorrect CAPTCHA answer. Please try again.");
-      generateNewCaptcha();
-      return;
-    }
-
     // Validate review
     if (rating < 1 || rating > 5) {
       alert("Please select a valid rating (1-5 stars)");
@@ -750,11 +748,22 @@ function BusinessDetailPage({ user }) {
     }
 
     try {
+      // Validate CAPTCHA on the Rust side
+      const solved = await invoke("verify_captcha", {
+        challengeId: captcha.id,
+        answer: userCaptchaAnswer
+      });
+      if (!solved) {
+        alert("Incorrect CAPTCHA answer. Please try again.");
+        generateNewCaptcha();
+        return;
+      }
+
       await invoke("create_review", {
         businessId: business.id,
-        userId: user.id,
         rating,
-        comment
+        comment,
+        captchaId: captcha.id
       });
 
       // Refresh reviews
@@ -770,7 +779,15 @@ function BusinessDetailPage({ user }) {
       alert("Thank you for your review! It has been submitted successfully.");
     } catch (err) {
       console.error("Failed to submit review:", err);
-      alert("Failed to submit review. Please try again.");
+      if (err?.details?.reason === "cooldown") {
+        alert(`You're posting too quickly. Please wait ${err.details.retry_after_seconds} seconds and try again.`);
+      } else if (err?.details?.reason === "hourly_limit") {
+        alert(`You can post up to ${err.details.limit} reviews per hour. Please try again in ${Math.ceil(err.details.retry_after_seconds / 60)} minutes.`);
+      } else if (err?.code === "VALIDATION" || err?.code === "CONFLICT") {
+        alert(err.message);
+      } else {
+        alert("Failed to submit review. Please try again.");
+      }
     }
   };
 
@@ -928,6 +945,9 @@ function BusinessDetailPage({ user }) {
                   <label>Human Verification (CAPTCHA):</label>
                   <div className="captcha-container">
                     <div className="captcha-question">{captcha.question}</div>
+                    {captcha.kind === "image" && (
+                      <img className="captcha-image" src={captcha.image} alt="CAPTCHA" />
+                    )}
                     <input
                       type="text"
                       placeholder="Your answer"
@@ -970,6 +990,12 @@ function BusinessDetailPage({ user }) {
                     </div>
                   </div>
                   <p className="review-comment">{review.comment}</p>
+                  {review.response && (
+                    <div className="review-response">
+                      <strong>Response from the owner</strong>
+                      <p>{review.response.comment}</p>
+                    </div>
+                  )}
                 </div>
               ))}
             </div>
@@ -981,7 +1007,100 @@ function BusinessDetailPage({ user }) {
 }
 
 // Profile Page
-function ProfilePage({ user }) {
+function AuthForm({ onSignedIn }) {
+  const [mode, setMode] = useState("sign_in");
+  const [submitting, setSubmitting] = useState(false);
+  const [error, setError] = useState(null);
+  const signingUp = mode === "sign_up";
+
+  const handleSubmit = async (e) => {
+    e.preventDefault();
+    const form = e.target;
+    setSubmitting(true);
+    setError(null);
+    try {
+      const user = signingUp
+        ? await invoke("sign_up", {
+            name: form.name.value,
+            email: form.email.value,
+            password: form.password.value
+          })
+        : await invoke("sign_in", {
+            email: form.email.value,
+            password: form.password.value
+          });
+      onSignedIn(user);
+    } catch (err) {
+      console.error(`Failed to ${signingUp ? "sign up" : "sign in"}:`, err);
+      if (err?.code === "VALIDATION") {
+        setError(err.details?.fields?.map(f => `${f.field} ${f.reason}`).join(", ") || err.message);
+      } else {
+        setError(err?.message || "Something went wrong. Please try again.");
+      }
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  return (
+    <div className="review-form-container">
+      <h4>{signingUp ? "Create an Account" : "Sign In"}</h4>
+      <form className="review-form" onSubmit={handleSubmit}>
+        {signingUp && (
+          <div className="form-group">
+            <label htmlFor="name">Name</label>
+            <input id="name" name="name" type="text" required />
+          </div>
+        )}
+        <div className="form-group">
+          <label htmlFor="email">Email</label>
+          <input id="email" name="email" type="email" required />
+        </div>
+        <div className="form-group">
+          <label htmlFor="password">Password</label>
+          <input id="password" name="password" type="password" required />
+        </div>
+        {error && <p className="form-error">{error}</p>}
+        <button type="submit" className="submit-review-button" disabled={submitting}>
+          {signingUp ? "Sign Up" : "Sign In"}
+        </button>
+      </form>
+      <button
+        type="button"
+        className="auth-toggle"
+        onClick={() => {
+          setMode(signingUp ? "sign_in" : "sign_up");
+          setError(null);
+        }}
+      >
+        {signingUp ? "Already have an account? Sign in" : "New here? Create an account"}
+      </button>
+    </div>
+  );
+}
+
+function ProfilePage({ user, onUserChange }) {
+  if (!user) {
+    return (
+      <div className="profile-page">
+        <header className="page-header">
+          <h2>Sign In</h2>
+          <p>Sign in to favorite businesses and write reviews</p>
+        </header>
+        <AuthForm onSignedIn={onUserChange} />
+      </div>
+    );
+  }
+
+  const handleSignOut = async () => {
+    try {
+      await invoke("sign_out");
+      onUserChange(null);
+    } catch (err) {
+      console.error("Failed to sign out:", err);
+    }
+  };
+
   return (
     <div className="profile-page">
       <header className="page-header">
@@ -1025,8 +1144,9 @@ function ProfilePage({ user }) {
               <strong>Email:</strong> {user?.email || "fbla@example.com"}
             </div>
             <div className="info-item">
-              <strong>Member Since:</strong> {new Date().toLocaleDateString()}
+              <strong>Member Since:</strong> {new Date(user.created_at).toLocaleDateString()}
             </div>
+            <button className="clear-data-button" onClick={handleSignOut}>Sign Out</button>
           </div>
 
           <div className="profile-section">